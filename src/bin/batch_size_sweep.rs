@@ -0,0 +1,134 @@
+// Sweep of the update batch size for single source shortest path in differential dataflow.
+//
+// The same `<rounds> * <per_update>` edge insertions are applied in batches of each of the
+// sizes given by `--batch-sizes`, every time on a fresh dataflow over the same initial graph.
+// The throughput of each batch size is printed as a table, to find where it stops improving.
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::timely_arguments;
+use graph_utility::GraphBenchmarkUpdates;
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+use graph_utility::sssp::sssp;
+
+use timely::dataflow::operators::probe::Handle;
+
+use differential_dataflow::input::Input;
+
+use std::time::Instant;
+
+/// Throughput gains below this fraction count as a plateau.
+const PLATEAU_GAIN: f64 = 0.1;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let total_updates = match benchmark.graph_updates {
+        GraphBenchmarkUpdates::RandomUpdates { edges_per_update, .. }
+        | GraphBenchmarkUpdates::AdversarialUpdates { edges_per_update, .. } => benchmark.num_rounds * edges_per_update,
+    };
+    if total_updates == 0 {
+        panic!("The sweep needs at least one update, pass positive <rounds> and <per_update>");
+    }
+    // Powers of ten up to the total number of updates, unless given.
+    let batch_sizes = benchmark.batch_sizes.clone().unwrap_or_else(|| {
+        let mut sizes = vec![1];
+        while sizes[sizes.len() - 1] * 10 <= total_updates {
+            sizes.push(sizes[sizes.len() - 1] * 10);
+        }
+        sizes
+    });
+    // All updates are generated as a single batch, so every batch size inserts the same edges.
+    let all_updates = match benchmark.graph_updates {
+        GraphBenchmarkUpdates::RandomUpdates { weight_par, .. }
+        | GraphBenchmarkUpdates::AdversarialUpdates { weight_par, .. } => {
+            GraphBenchmarkUpdates::RandomUpdates { edges_per_update: total_updates, weight_par: weight_par, deletion_ratio: 0.0 }
+        }
+    };
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        let source = benchmark.search_query.source;
+        let mut throughputs = Vec::new();
+
+        for batch_size in batch_sizes.iter().cloned() {
+            let mut probe = Handle::new();
+            let (mut roots, mut graph_in) = worker.dataflow(|scope| {
+                let (root_input, roots) = scope.new_collection();
+                let (edge_input, graph) = scope.new_collection();
+                sssp(&graph, &roots).probe_with(&mut probe);
+                (root_input, edge_input)
+            });
+            roots.insert(source);
+            roots.close();
+
+            // The same seed for every batch size generates the same graph and updates.
+            let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+            let mut updates = Vec::new();
+            if worker_index == 0 {
+                timer.time_subevent(&format!("Loading {}", batch_size), || {
+                    // Update data only on one worker.
+                    for edge in gen.gen_initial_graph(&benchmark.graph_data).into_iter() {
+                        graph_in.update_at(edge, Default::default(), 1);
+                    }
+                });
+                updates = gen.gen_graph_updates(&all_updates);
+            }
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+
+            // Every worker runs the same number of rounds, while only worker 0 has updates.
+            let num_batches = (total_updates + batch_size - 1) / batch_size;
+            let sweep_timer = Instant::now();
+            for batch in 0..num_batches {
+                let begin = (batch * batch_size) as usize;
+                let end = std::cmp::min(begin + batch_size as usize, updates.len());
+                for edge in updates[std::cmp::min(begin, end)..end].iter() {
+                    graph_in.update_at(*edge, 1 + batch, 1);
+                }
+                graph_in.advance_to(2 + batch);
+                // Flush to input to make sure all changes are in the message queues.
+                graph_in.flush();
+                worker.step_while(|| probe.less_than(&graph_in.time()));
+            }
+            let elapsed = sweep_timer.elapsed();
+            throughputs.push((batch_size, num_batches, elapsed, total_updates as f64 / elapsed.as_secs_f64()));
+        }
+
+        if worker_index == 0 {
+            println!("{:>12}{:>10}{:>18}{:>16}", "Batch size", "Batches", "Time", "Edges/sec");
+            for (batch_size, num_batches, elapsed, throughput) in throughputs.iter() {
+                println!("{:>12}{:>10}{:>18}{:>16.0}", batch_size, num_batches, format!("{:?}", elapsed), throughput);
+            }
+            // The knee is the first batch size after which throughput stops improving notably.
+            let knee = throughputs
+                .windows(2)
+                .find(|pair| pair[1].3 < pair[0].3 * (1.0 + PLATEAU_GAIN))
+                .map(|pair| pair[0].0);
+            match knee {
+                Some(batch_size) => println!("Throughput plateaus from batch size {}", batch_size),
+                None => println!("Throughput still improves at the largest batch size"),
+            }
+        }
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
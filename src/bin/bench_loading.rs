@@ -0,0 +1,79 @@
+/// Benchmark of the graph loaders in isolation, without running any algorithm.
+/// Reports the loading throughput in edges and megabytes per second.
+
+extern crate graph_utility;
+
+use graph_utility::GraphLoader;
+use graph_utility::SubEventTimer;
+
+fn main() {
+
+    // Test arguments:
+    // executable   format     path                   weights?
+    // bench_loading unweighted data/roadNet-dummy.txt
+    // bench_loading edge-id    topology.txt           weights.txt
+    // bench_loading weighted-cached graph.txt         graph.bin
+    //
+    // Formats: weighted, weighted-stream, weighted-remapped, weighted-cached, csv, unweighted, capacitated, binary, adjacency, metis, mtx, edge-id
+
+    let mut arguments = std::env::args();
+    arguments.next().expect("Command line argument should contain an executable name.");
+    let format: String = arguments.next().expect("No graph format given");
+    let path: String = arguments.next().expect("No path to graph file given");
+    let mut paths = vec![path.clone()];
+
+    let timer = SubEventTimer::new_timer();
+    // CSV files are expected to start with a header row. Progress goes to standard error.
+    let loader = GraphLoader::default().with_header(format == "csv").with_progress(true);
+    let num_edges = timer.time_subevent("Loading", || match format.as_str() {
+        "weighted" => loader.load_weighted_graph_or_panic(&path).len(),
+        "weighted-stream" => loader
+            .stream_weighted_graph(&path)
+            .map(|edge| edge.unwrap_or_else(|error| panic!("{}", error)))
+            .count(),
+        "weighted-remapped" => loader
+            .load_weighted_graph_remapped(&path)
+            .unwrap_or_else(|error| panic!("{}", error))
+            .0
+            .len(),
+        "weighted-cached" => {
+            // Only the text file counts towards the throughput, whether or not the cache is used.
+            let cache_path = arguments.next().expect("No path to cache file given");
+            loader
+                .load_weighted_graph_cached(&path, &cache_path)
+                .unwrap_or_else(|error| panic!("{}", error))
+                .len()
+        }
+        "csv" => loader
+            .load_weighted_graph_csv(&path)
+            .unwrap_or_else(|error| panic!("{}", error))
+            .len(),
+        "unweighted" => loader.load_unweighted_graph_or_panic(&path).len(),
+        "capacitated" => loader
+            .load_capacitated_graph(&path)
+            .unwrap_or_else(|error| panic!("{}", error))
+            .len(),
+        "binary" => loader.load_binary_graph(&path).len(),
+        "adjacency" => loader.load_adjacency_matrix_or_panic(&path).len(),
+        "metis" => loader.load_metis_or_panic(&path).len(),
+        "mtx" => loader.load_matrix_market_or_panic(&path).len(),
+        "edge-id" => {
+            let weights_path = arguments.next().expect("No path to edge weights file given");
+            paths.push(weights_path.clone());
+            loader
+                .load_weighted_graph_by_edge_id(&path, &weights_path)
+                .unwrap_or_else(|error| panic!("{}", error))
+                .len()
+        }
+        _ => panic!("Invalid graph format passed. Please use one of: weighted, weighted-stream, weighted-remapped, weighted-cached, csv, unweighted, capacitated, binary, adjacency, metis, mtx, edge-id"),
+    });
+    let elapsed = timer.elapsed();
+    let seconds = elapsed.as_secs_f64();
+
+    let bytes: u64 = paths
+        .iter()
+        .map(|path| std::fs::metadata(path).expect("Could not read file metadata").len())
+        .sum();
+    println!("Loaded {} edges from {} bytes in {:?}", num_edges, bytes, elapsed);
+    println!("Throughput: {:.0} edges/sec, {:.2} MB/sec", num_edges as f64 / seconds, bytes as f64 / 1e6 / seconds);
+}
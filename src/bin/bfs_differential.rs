@@ -0,0 +1,199 @@
+// Breadth first search in differential dataflow
+//
+// Computes the number of hops from the source to every reachable node. Edge weights
+// of the generated or loaded graph are ignored.
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::timely_arguments;
+use graph_utility::GraphBenchmarkUpdates;
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+use graph_utility::workers::gen_initial_graph_parallel;
+
+use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::*;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::*;
+use differential_dataflow::Collection;
+
+type Node = u32;
+type Edge = (Node, Node);
+type Hops = u32;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let inspect: bool = benchmark.inspect_results;
+    let target = benchmark.search_query.target;
+    // Deletions are drawn from the edges in the graph, which the generator then keeps track of.
+    let deletions = match benchmark.graph_updates {
+        GraphBenchmarkUpdates::RandomUpdates { deletion_ratio, .. } => deletion_ratio > 0.0,
+        GraphBenchmarkUpdates::AdversarialUpdates { .. } => false,
+    };
+    if benchmark.parallel_loading && deletions {
+        panic!("--parallel-loading cannot be combined with --deletion-ratio, which needs the whole graph on worker 0");
+    }
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        // define BFS dataflow; return handles to roots and edges inputs
+        let mut probe = Handle::new();
+        let (mut roots, mut graph_in) = worker.dataflow(|scope| {
+            let (root_input, roots) = scope.new_collection();
+            let (edge_input, graph) = scope.new_collection();
+            let mut result = bfs(&graph, &roots);
+
+            if benchmark.count_reachable {
+                // One record per reached node, counted under a single key.
+                result
+                    .map(|_| ())
+                    .count()
+                    .inspect(|(((), count), time, diff)| {
+                        if *diff > 0 {
+                            println!("Reachable nodes at {:?}: {}", time, count);
+                        }
+                    })
+                    .probe_with(&mut probe);
+            }
+
+            if inspect {
+                result = result.filter(move |(n, _)| *n == target);
+            } else {
+                result = result.filter(|_| false);
+            }
+
+            result
+                .map(|(_, l)| l)
+                .consolidate()
+                .inspect(|x| println!("Target node: {:?}", x))
+                .probe_with(&mut probe);
+
+            (root_input, edge_input)
+        });
+        let source = benchmark.search_query.source;
+        roots.insert(source);
+        roots.close();
+
+        // Random generator engine.
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric).with_history(deletions);
+        if benchmark.parallel_loading {
+            // Every worker loads and inserts its own share of the initial graph.
+            let mut load = || {
+                let initial_edges = gen_initial_graph_parallel(worker, &mut gen, &benchmark.graph_data)
+                    .unwrap_or_else(|error| panic!("{}", error));
+                if worker_index == 0 {
+                    println!(
+                        "Performing BFS on {} nodes, {} edges:",
+                        gen.max_num_nodes(),
+                        gen.total_edges_emitted()
+                    );
+                }
+                for (from, to, _) in initial_edges.into_iter() {
+                    graph_in.update_at((from, to), Default::default(), 1);
+                }
+            };
+            if worker_index == 0 {
+                timer.time_subevent("Loading", load);
+            } else {
+                load();
+            }
+        } else if worker_index == 0 {
+            timer.time_subevent("Loading", || {
+                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                println!(
+                    "Performing BFS on {} nodes, {} edges:",
+                    gen.max_num_nodes(),
+                    initial_edges.len()
+                );
+                // Update data only on one worker.
+                for (from, to, _) in initial_edges.into_iter() {
+                    graph_in.update_at((from, to), Default::default(), 1);
+                }
+            });
+        }
+        let mut initial_advance = || {
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+        };
+        if worker_index == 0 {
+            timer.time_subevent("Initial", initial_advance);
+        } else {
+            initial_advance();
+        }
+
+        // Warmup rounds run first, through the same update path, but are neither timed nor reported.
+        let warmup_rounds = benchmark.warmup_rounds;
+        let num_rounds = benchmark.num_rounds;
+        let rounds_per_batch = benchmark.rounds_per_batch;
+        for round in 0..warmup_rounds + num_rounds {
+            if worker.index() == 0 {
+                // Insert and delete elements for update
+                for ((from, to, _), diff) in gen.gen_graph_changes_near(&benchmark.graph_updates, &[]).into_iter() {
+                    graph_in.update_at((from, to), 1 + round, diff);
+                }
+            }
+            if !benchmark.ends_batch(round) {
+                // The changes stay at their round's time until the batch is complete.
+                continue;
+            }
+            graph_in.advance_to(2 + round);
+            // Flush to input to make sure all changes are in the message queues.
+            graph_in.flush();
+            let mut update_advance = || {
+                worker.step_while(|| probe.less_than(&graph_in.time()));
+            };
+            if round < warmup_rounds {
+                update_advance();
+            } else if worker_index == 0 {
+                timer.time_subevent(&format!("N {}", (round - warmup_rounds) / rounds_per_batch), update_advance);
+            } else {
+                update_advance();
+            }
+        }
+
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
+
+// returns pairs (n, h) indicating node n can be reached from a root in h hops.
+fn bfs<G: Scope>(
+    edges: &Collection<G, Edge>,
+    roots: &Collection<G, Node>,
+) -> Collection<G, (Node, Hops)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // initialize roots as reaching themselves at distance 0
+    let nodes = roots.map(|x| (x, 0));
+    // Repeatedly update minimal hop counts each node can be reached from each root
+    nodes.iterate(|inner| {
+        let edges = edges.enter(&inner.scope());
+        let nodes = nodes.enter(&inner.scope());
+        inner
+            .join_map(&edges, |_from, &hops, &to| (to, hops + 1))
+            .concat(&nodes)
+            // Note: reduce receives its input as an ordered collection.
+            .reduce(|_, input, output| output.push((*input[0].0, 1)))
+    })
+}
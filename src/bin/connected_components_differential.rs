@@ -0,0 +1,196 @@
+// Weakly connected components in differential dataflow
+//
+// Labels every node with the smallest node ID of its component, following edges in
+// both directions. Each update round inserts new edges, which can only merge components.
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::timely_arguments;
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+use graph_utility::workers::gen_initial_graph_parallel;
+
+use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::operators::{Exchange, Inspect, Probe};
+use timely::dataflow::*;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::*;
+use differential_dataflow::Collection;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+type Node = u32;
+type Edge = (Node, Node);
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let inspect: bool = benchmark.inspect_results;
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        let mut probe = Handle::new();
+        // Accumulated counts of the number of components gathered on worker 0.
+        let num_components = Rc::new(RefCell::new(HashMap::new()));
+        let mut graph_in = worker.dataflow(|scope| {
+            let (edge_input, graph) = scope.new_collection();
+            let labels = connected_components(&graph);
+
+            if inspect {
+                let num_components = num_components.clone();
+                labels
+                    .map(|(_, label)| label)
+                    .distinct()
+                    .map(|_| ())
+                    .count()
+                    .inner
+                    .exchange(|_| 0)
+                    .inspect(move |(((), count), _time, diff)| {
+                        let mut num_components = num_components.borrow_mut();
+                        let retracted = {
+                            let total = num_components.entry(*count).or_insert(0);
+                            *total += *diff;
+                            *total == 0
+                        };
+                        if retracted {
+                            num_components.remove(count);
+                        }
+                    })
+                    .probe_with(&mut probe);
+            } else {
+                labels.probe_with(&mut probe);
+            }
+
+            edge_input
+        });
+
+        // Random generator engine.
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        if benchmark.parallel_loading {
+            // Every worker loads and inserts its own share of the initial graph.
+            let mut load = || {
+                let initial_edges = gen_initial_graph_parallel(worker, &mut gen, &benchmark.graph_data)
+                    .unwrap_or_else(|error| panic!("{}", error));
+                if worker_index == 0 {
+                    println!(
+                        "Performing CC on {} nodes, {} edges:",
+                        gen.max_num_nodes(),
+                        gen.total_edges_emitted()
+                    );
+                }
+                for (from, to, _) in initial_edges.into_iter() {
+                    graph_in.update_at((from, to), Default::default(), 1);
+                }
+            };
+            if worker_index == 0 {
+                timer.time_subevent("Loading", load);
+            } else {
+                load();
+            }
+        } else if worker_index == 0 {
+            timer.time_subevent("Loading", || {
+                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                println!(
+                    "Performing CC on {} nodes, {} edges:",
+                    gen.max_num_nodes(),
+                    initial_edges.len()
+                );
+                // Update data only on one worker.
+                for (from, to, _) in initial_edges.into_iter() {
+                    graph_in.update_at((from, to), Default::default(), 1);
+                }
+            });
+        }
+        let mut initial_advance = || {
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+        };
+        if worker_index == 0 {
+            timer.time_subevent("Initial", initial_advance);
+        } else {
+            initial_advance();
+        }
+
+        // Warmup rounds run first, through the same update path, but are neither timed nor reported.
+        let warmup_rounds = benchmark.warmup_rounds;
+        let num_rounds = benchmark.num_rounds;
+        let rounds_per_batch = benchmark.rounds_per_batch;
+        for round in 0..warmup_rounds + num_rounds {
+            if worker.index() == 0 {
+                // Insert elements for update
+                for (from, to, _) in gen.gen_graph_updates(&benchmark.graph_updates).into_iter() {
+                    graph_in.update_at((from, to), 1 + round, 1);
+                }
+            }
+            if !benchmark.ends_batch(round) {
+                // The changes stay at their round's time until the batch is complete.
+                continue;
+            }
+            graph_in.advance_to(2 + round);
+            // Flush to input to make sure all changes are in the message queues.
+            graph_in.flush();
+            let mut update_advance = || {
+                worker.step_while(|| probe.less_than(&graph_in.time()));
+            };
+            if round < warmup_rounds {
+                update_advance();
+            } else if worker_index == 0 {
+                timer.time_subevent(&format!("N {}", (round - warmup_rounds) / rounds_per_batch), update_advance);
+            } else {
+                update_advance();
+            }
+        }
+
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if inspect {
+                // An empty graph has no components, and so no count.
+                let count = num_components.borrow().keys().next().cloned().unwrap_or(0);
+                println!("Connected components: {}", count);
+            }
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
+
+// returns pairs (n, l) indicating node n is in the component with smallest node l.
+fn connected_components<G: Scope>(
+    edges: &Collection<G, Edge>,
+) -> Collection<G, (Node, Node)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // Weak connectivity follows the edges in both directions.
+    let edges = edges.concat(&edges.map(|(from, to)| (to, from)));
+    // initialize every node with its own ID as the label
+    let nodes = edges.map(|(from, _)| (from, from)).distinct();
+    // Repeatedly propagate the smallest label to the neighbours
+    nodes.iterate(|inner| {
+        let edges = edges.enter(&inner.scope());
+        let nodes = nodes.enter(&inner.scope());
+        inner
+            .join_map(&edges, |_from, &label, &to| (to, label))
+            .concat(&nodes)
+            // Note: reduce receives its input as an ordered collection.
+            .reduce(|_, input, output| output.push((*input[0].0, 1)))
+    })
+}
@@ -0,0 +1,141 @@
+// Earliest arrival paths on temporal graphs in differential dataflow.
+//
+// Every edge is a connection with a departure and an arrival time, as in a public
+// transit timetable. A connection can only be taken by arriving at its source no
+// later than its departure, so the search propagates arrival times rather than
+// summing weights.
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::timely_arguments;
+use graph_utility::GraphBenchmarkData;
+use graph_utility::WeightMode;
+use graph_utility::GraphLoader;
+use graph_utility::SubEventTimer;
+use graph_utility::{Node, TemporalEdge, Time};
+
+use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::*;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::*;
+use differential_dataflow::Collection;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let inspect: bool = benchmark.inspect_results;
+    let target = benchmark.search_query.target;
+    let start_time = benchmark.start_time;
+    // Timetables are only read from files of "source target departure arrival" lines.
+    let path = match &benchmark.graph_data {
+        GraphBenchmarkData::RealWorldGraph { path_to_edge_list, weight_mode: WeightMode::UseFile } => path_to_edge_list.clone(),
+        _ => panic!("Temporal graphs must be loaded with the real data type, without weight generation"),
+    };
+    if benchmark.num_rounds > 0 {
+        println!("Update rounds are not supported on temporal graphs and are skipped");
+    }
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        let mut probe = Handle::new();
+        let (mut roots, mut graph_in) = worker.dataflow(|scope| {
+            let (root_input, roots) = scope.new_collection();
+            let (edge_input, graph) = scope.new_collection();
+            let mut result = earliest_arrival(&graph, &roots);
+
+            if inspect {
+                result = result.filter(move |(n, _)| *n == target);
+            } else {
+                result = result.filter(|_| false);
+            }
+
+            result
+                .map(|(_, arrival)| arrival)
+                .consolidate()
+                .inspect(move |(arrival, _time, diff)| {
+                    if *diff > 0 {
+                        println!("Earliest arrival at target {}: {}", target, arrival);
+                    }
+                })
+                .probe_with(&mut probe);
+
+            (root_input, edge_input)
+        });
+        let source = benchmark.search_query.source;
+        roots.insert((source, start_time));
+        roots.close();
+
+        if worker_index == 0 {
+            timer.time_subevent("Loading", || {
+                let connections = GraphLoader::default()
+                    .load_temporal_graph(&path)
+                    .unwrap_or_else(|error| panic!("{}", error));
+                println!(
+                    "Performing earliest arrival search from time {} on {} connections:",
+                    start_time,
+                    connections.len()
+                );
+                // Update data only on one worker.
+                for connection in connections.into_iter() {
+                    graph_in.update_at(connection, Default::default(), 1);
+                }
+            });
+        }
+        let mut initial_advance = || {
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+        };
+        if worker_index == 0 {
+            timer.time_subevent("Initial", initial_advance);
+        } else {
+            initial_advance();
+        }
+
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
+
+// returns pairs (n, t) indicating node n can be reached from a root, starting at the
+// root's time, with the earliest arrival time t.
+fn earliest_arrival<G: Scope>(
+    connections: &Collection<G, TemporalEdge>,
+    roots: &Collection<G, (Node, Time)>,
+) -> Collection<G, (Node, Time)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // Repeatedly take the connections departing after the arrival at their source
+    roots.iterate(|inner| {
+        let connections = connections
+            .enter(&inner.scope())
+            .map(|(from, to, departure, arrival)| (from, (to, departure, arrival)));
+        let roots = roots.enter(&inner.scope());
+        inner
+            .join_map(&connections, |_from, &time, &(to, departure, arrival)| (to, time, departure, arrival))
+            .filter(|(_, time, departure, _)| time <= departure)
+            .map(|(to, _, _, arrival)| (to, arrival))
+            .concat(&roots)
+            // Note: reduce receives its input as an ordered collection.
+            .reduce(|_, input, output| output.push((*input[0].0, 1)))
+    })
+}
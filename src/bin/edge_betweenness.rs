@@ -0,0 +1,130 @@
+/// Baseline edge betweenness centrality using Brandes' algorithm with
+/// Dijkstra searches, as used by Girvan-Newman community detection.
+/// With `--sample-sources` only a random sample of the sources is searched
+/// and the result is scaled up to estimate the exact value.
+
+extern crate graph_utility;
+
+use graph_utility::default_rng;
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::sample_nodes;
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+use graph_utility::{Node, Weight, WeightedEdge};
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Number of edges with the highest betweenness to print.
+const TOP_EDGES: usize = 10;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Measure data loading.
+    let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+    let edges = timer.time_subevent("Loading", || {
+        let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+        println!(
+            "Computing edge betweenness on {} nodes, {} edges:",
+            gen.max_num_nodes(),
+            initial_edges.len()
+        );
+        initial_edges
+    });
+    let num_nodes = gen.max_num_nodes();
+    let sources: Vec<Node> = match benchmark.sample_sources {
+        Some(amount) => sample_nodes(&mut default_rng(benchmark.sample_seed), num_nodes, amount),
+        None => (0..num_nodes).collect(),
+    };
+
+    let betweenness = timer.time_subevent("Initial", || edge_betweenness(&edges, num_nodes, &sources));
+    println!(
+        "Brandes edge betweenness from {} sources finished in: {:?}",
+        sources.len(),
+        timer.elapsed()
+    );
+
+    let mut ranked: Vec<usize> = (0..edges.len()).collect();
+    ranked.sort_by(|a, b| betweenness[*b].partial_cmp(&betweenness[*a]).expect("Betweenness is never NaN"));
+    for id in ranked.into_iter().take(TOP_EDGES) {
+        let (from, to, weight) = edges[id];
+        println!("Edge {} -> {} (weight {}): {:.3}", from, to, weight, betweenness[id]);
+    }
+    if let Some(path) = &benchmark.trace_output {
+        timer.write_chrome_trace(path).expect("Could not write trace file");
+    }
+}
+
+/// Betweenness of each edge, indexed by its position in `edges`. When `sources` is
+/// a sample of the nodes, the sums are scaled by `num_nodes / sources.len()`.
+fn edge_betweenness(edges: &[WeightedEdge], num_nodes: u32, sources: &[Node]) -> Vec<f64> {
+    let num_nodes = num_nodes as usize;
+    let mut adjacency: Vec<Vec<(Node, Weight, usize)>> = vec![Vec::new(); num_nodes];
+    for (id, (from, to, weight)) in edges.iter().enumerate() {
+        adjacency[*from as usize].push((*to, *weight, id));
+    }
+
+    let mut betweenness = vec![0.0; edges.len()];
+    for source in sources.iter() {
+        // Dijkstra search counting the shortest paths to each node.
+        let mut distance: Vec<Option<u64>> = vec![None; num_nodes];
+        let mut paths = vec![0.0f64; num_nodes];
+        let mut predecessors: Vec<Vec<(Node, usize)>> = vec![Vec::new(); num_nodes];
+        // Nodes in order of non-decreasing distance.
+        let mut settled = Vec::new();
+        let mut done = vec![false; num_nodes];
+        let mut heap = BinaryHeap::new();
+        distance[*source as usize] = Some(0);
+        paths[*source as usize] = 1.0;
+        heap.push(Reverse((0u64, *source)));
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            if done[node as usize] {
+                continue;
+            }
+            done[node as usize] = true;
+            settled.push(node);
+            for (next, weight, id) in adjacency[node as usize].iter() {
+                let next_cost = cost + *weight as u64;
+                let next_index = *next as usize;
+                match distance[next_index] {
+                    Some(known) if known < next_cost => {}
+                    Some(known) if known == next_cost => {
+                        paths[next_index] += paths[node as usize];
+                        predecessors[next_index].push((node, *id));
+                    }
+                    _ => {
+                        distance[next_index] = Some(next_cost);
+                        paths[next_index] = paths[node as usize];
+                        predecessors[next_index].clear();
+                        predecessors[next_index].push((node, *id));
+                        heap.push(Reverse((next_cost, *next)));
+                    }
+                }
+            }
+        }
+
+        // Accumulate the dependencies from the farthest nodes back to the source.
+        let mut dependency = vec![0.0f64; num_nodes];
+        while let Some(node) = settled.pop() {
+            for (previous, id) in predecessors[node as usize].iter() {
+                let share = paths[*previous as usize] / paths[node as usize] * (1.0 + dependency[node as usize]);
+                betweenness[*id] += share;
+                dependency[*previous as usize] += share;
+            }
+        }
+    }
+
+    if !sources.is_empty() && sources.len() < num_nodes {
+        let scale = num_nodes as f64 / sources.len() as f64;
+        for value in betweenness.iter_mut() {
+            *value *= scale;
+        }
+    }
+    betweenness
+}
@@ -0,0 +1,179 @@
+// Estimate of the effective diameter in differential dataflow: the distance within
+// which a given percentile, 90% by default, of the reachable pairs of nodes fall.
+//
+// The exact diameter needs the distances between all pairs of nodes and is sensitive
+// to a few outliers. Instead, the distances are computed from a seeded random sample
+// of source nodes and the percentile is taken from their finite distances.
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::default_rng;
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::sample_nodes;
+use graph_utility::timely_arguments;
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+
+use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::*;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::*;
+use differential_dataflow::Collection;
+
+type Node = u32;
+type Weight = u32;
+type Edge = (Node, Node, Weight);
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let inspect: bool = benchmark.inspect_results;
+    let percentile = benchmark.percentile;
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        let mut probe = Handle::new();
+        let (mut roots, mut graph_in) = worker.dataflow(|scope| {
+            let (root_input, roots) = scope.new_collection();
+            let (edge_input, graph) = scope.new_collection();
+            let mut result = multi_source_sssp(&graph, &roots);
+
+            if !inspect {
+                result = result.filter(|_| false);
+            }
+
+            // Smallest distance covering the percentile of the pairs, under a single key.
+            result
+                .filter(|((root, node), _)| root != node)
+                .map(|(_, distance)| ((), distance))
+                .reduce(move |_, input, output| {
+                    let pairs: u64 = input.iter().map(|(_, count)| *count as u64).sum();
+                    let mut covered: u64 = 0;
+                    // Note: reduce receives its input as an ordered collection.
+                    for (distance, count) in input.iter() {
+                        covered += *count as u64;
+                        if covered as f64 >= pairs as f64 * percentile / 100.0 {
+                            output.push(((**distance, pairs), 1isize));
+                            break;
+                        }
+                    }
+                })
+                .inspect(move |(((), (distance, pairs)), time, diff)| {
+                    if *diff > 0 {
+                        println!(
+                            "Effective diameter ({}th percentile) at {:?}: {} over {} pairs",
+                            percentile,
+                            time,
+                            distance,
+                            pairs
+                        );
+                    }
+                })
+                .probe_with(&mut probe);
+
+            (root_input, edge_input)
+        });
+
+        // Random generator engine.
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        if worker_index == 0 {
+            timer.time_subevent("Loading", || {
+                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                let mut rng = default_rng(benchmark.sample_seed);
+                let num_sources = benchmark.sample_sources.unwrap_or(10);
+                let sources = sample_nodes(&mut rng, gen.max_num_nodes(), num_sources);
+                println!(
+                    "Effective diameter from {} sources on {} nodes, {} edges:",
+                    sources.len(),
+                    gen.max_num_nodes(),
+                    initial_edges.len()
+                );
+                // Update data only on one worker.
+                for source in sources.into_iter() {
+                    roots.insert(source);
+                }
+                for edge in initial_edges.iter() {
+                    graph_in.update_at(*edge, Default::default(), 1);
+                }
+            });
+        }
+        roots.close();
+        let mut initial_advance = || {
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+        };
+        if worker_index == 0 {
+            timer.time_subevent("Initial", initial_advance);
+        } else {
+            initial_advance();
+        }
+
+        let num_rounds = benchmark.num_rounds;
+        for round in 0..num_rounds {
+            if worker.index() == 0 {
+                let batch_edges = gen.gen_graph_updates(&benchmark.graph_updates);
+                // Insert elements for update
+                for edge in batch_edges.into_iter() {
+                    graph_in.update_at(edge, 1 + round, 1);
+                }
+            }
+            graph_in.advance_to(2 + round);
+            // Flush to input to make sure all changes are in the message queues.
+            graph_in.flush();
+            let mut update_advance = || {
+                worker.step_while(|| probe.less_than(&graph_in.time()));
+            };
+            if worker_index == 0 {
+                timer.time_subevent(&format!("N {}", round), update_advance);
+            } else {
+                update_advance();
+            }
+        }
+
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
+
+// returns triples ((r, n), d) indicating node n can be reached from root r at distance d.
+fn multi_source_sssp<G: Scope>(
+    edges: &Collection<G, Edge>,
+    roots: &Collection<G, Node>,
+) -> Collection<G, ((Node, Node), Weight)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // initialize roots as reaching themselves at distance 0
+    let nodes = roots.map(|x| ((x, x), 0));
+    // Repeatedly update minimal distances each node can be reached from each root
+    nodes.iterate(|inner| {
+        let edges = edges
+            .enter(&inner.scope())
+            .map(|(from, to, w)| (from, (to, w)));
+        let nodes = nodes.enter(&inner.scope());
+        inner
+            .map(|((root, node), cost)| (node, (root, cost)))
+            .join_map(&edges, |_from, &(root, cost), &(to, w)| ((root, to), cost + w))
+            .concat(&nodes)
+            // Note: reduce receives its input as an ordered collection.
+            .reduce(|_, input, output| output.push((*input[0].0, 1)))
+    })
+}
@@ -0,0 +1,154 @@
+// K shortest path distances in differential dataflow
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::timely_arguments;
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+
+use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::*;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::*;
+use differential_dataflow::Collection;
+
+type Node = u32;
+type Weight = u32;
+type Edge = (Node, Node, Weight);
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let inspect: bool = benchmark.inspect_results;
+    let target = benchmark.search_query.target;
+    let k = benchmark.num_paths;
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        let mut probe = Handle::new();
+        let (mut roots, mut graph_in) = worker.dataflow(|scope| {
+            let (root_input, roots) = scope.new_collection();
+            let (edge_input, graph) = scope.new_collection();
+            let mut result = k_shortest_paths(&graph, &roots, k);
+
+            if inspect {
+                result = result.filter(move |(n, _)| *n == target);
+            } else {
+                result = result.filter(|_| false);
+            }
+
+            result
+                .map(|(_, l)| l)
+                .consolidate()
+                .inspect(|x| println!("Target node: {:?}", x))
+                .probe_with(&mut probe);
+
+            (root_input, edge_input)
+        });
+        let source = benchmark.search_query.source;
+        roots.insert(source);
+        roots.close();
+
+        // Random generator engine.
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        if worker_index == 0 {
+            timer.time_subevent("Loading", || {
+                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                println!(
+                    "Performing {}-SSSP on {} nodes, {} edges:",
+                    k,
+                    gen.max_num_nodes(),
+                    initial_edges.len()
+                );
+                // Update data only on one worker.
+                for edge in initial_edges.iter() {
+                    graph_in.update_at(*edge, Default::default(), 1);
+                }
+            });
+        }
+        let mut initial_advance = || {
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+        };
+        if worker_index == 0 {
+            timer.time_subevent("Initial", initial_advance);
+        } else {
+            initial_advance();
+        }
+
+        let num_rounds = benchmark.num_rounds;
+        for round in 0..num_rounds {
+            if worker.index() == 0 {
+                let batch_edges = gen.gen_graph_updates(&benchmark.graph_updates);
+                // Insert elements for update
+                for edge in batch_edges.into_iter() {
+                    graph_in.update_at(edge, 1 + round, 1);
+                }
+            }
+            graph_in.advance_to(2 + round);
+            // Flush to input to make sure all changes are in the message queues.
+            graph_in.flush();
+            let mut update_advance = || {
+                worker.step_while(|| probe.less_than(&graph_in.time()));
+            };
+            if worker_index == 0 {
+                timer.time_subevent(&format!("N {}", round), update_advance);
+            } else {
+                update_advance();
+            }
+        }
+
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
+
+// returns pairs (n, d) for the k smallest distinct distances d at which node n
+// can be reached from a root.
+fn k_shortest_paths<G: Scope>(
+    edges: &Collection<G, Edge>,
+    roots: &Collection<G, Node>,
+    k: usize,
+) -> Collection<G, (Node, Weight)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // initialize roots as reaching themselves at distance 0
+    let nodes = roots.map(|x| (x, 0));
+    // Repeatedly extend the k best distances of each node along its outgoing edges.
+    nodes.iterate(|inner| {
+        let edges = edges
+            .enter(&inner.scope())
+            .map(|(from, to, w)| (from, (to, w)));
+        let nodes = nodes.enter(&inner.scope());
+        inner
+            .join_map(&edges, |_from, &cost, &(to, w)| (to, cost + w))
+            .concat(&nodes)
+            // Note: reduce receives its input as an ordered collection,
+            // so the bounded list is the first k distinct distances.
+            .reduce(move |_, input, output| {
+                for (distance, _) in input.iter().take(k) {
+                    output.push((**distance, 1));
+                }
+            })
+    })
+}
@@ -1,7 +1,14 @@
 extern crate graph_utility;
 
 use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::GraphBenchmarkData;
+use graph_utility::WeightMode;
 use graph_utility::GraphDataGenerator;
+use graph_utility::GraphLoader;
+use graph_utility::partition_edge_cut;
+use graph_utility::degree_assortativity;
+use graph_utility::graph_stats;
+use graph_utility::weight_histogram;
 
 fn main() {
 
@@ -16,8 +23,42 @@ fn main() {
 
     println!("{:?}", benchmark);
 
-    let mut gen = GraphDataGenerator::new_from_seed(10);
+    let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
     let edge_list = gen.gen_initial_graph(&benchmark.graph_data);
+    println!("Graph stats: {}", graph_stats(&edge_list));
+
+    for parts in [2, 4, 8].iter() {
+        let cut = partition_edge_cut(&edge_list, *parts);
+        println!("Edge cut for {} parts: {} {:?}", parts, cut.iter().sum::<usize>(), cut);
+    }
+
+    match degree_assortativity(&edge_list) {
+        Some(coefficient) => println!("Degree assortativity: {:.4}", coefficient),
+        None => println!("Degree assortativity: undefined, the degrees do not vary"),
+    }
+
+    // The spread of the weights, for checking the weight distribution.
+    for (low, count) in weight_histogram(&edge_list, 10).into_iter() {
+        println!("Weights from {:10}: {}", low, count);
+    }
+
+    // The share of each peer of a weighted file, and how much cleaning it would remove.
+    if let GraphBenchmarkData::RealWorldGraph { path_to_edge_list, weight_mode: WeightMode::UseFile } = &benchmark.graph_data {
+        let single = GraphLoader::default().load_weighted_graph_or_panic(path_to_edge_list);
+        let peers = 4;
+        for index in 0..peers {
+            let part = GraphLoader::new(index, peers)
+                .load_weighted_graph_partitioned(path_to_edge_list)
+                .unwrap_or_else(|error| panic!("{}", error));
+            println!("Partition {} of {}: {} edges", index, peers, part.len());
+        }
+
+        let cleaned = GraphLoader::default()
+            .with_dedup(true)
+            .with_drop_self_loops(true)
+            .load_weighted_graph_or_panic(path_to_edge_list);
+        println!("Edges without duplicates and self loops: {} of {}", cleaned.len(), single.len());
+    }
 
     for edge in edge_list.into_iter().take(100) {
         println!("Edge: {:?}", edge);
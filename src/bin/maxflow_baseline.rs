@@ -0,0 +1,120 @@
+/// Baseline maximum flow computation on a petgraph graph, using the
+/// Edmonds-Karp algorithm (shortest augmenting paths).
+
+extern crate graph_utility;
+extern crate petgraph;
+
+use graph_utility::GraphBenchmarkData;
+use graph_utility::WeightMode;
+use graph_utility::GraphDataGenerator;
+use graph_utility::GraphLoader;
+use graph_utility::SubEventTimer;
+use graph_utility::parse_graph_benchmark_arguments;
+
+use petgraph::prelude::*;
+use petgraph::Graph;
+
+use std::collections::VecDeque;
+
+/// Graph of residual capacities. Every input edge is stored next to its
+/// reverse residual edge, so edge `2i` and `2i + 1` form a pair.
+type ResidualGraph = Graph<(), u32, Directed, u32>;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Measure data loading.
+    let mut graph = timer.time_subevent("Loading", || {
+        // Files without weight generation carry a capacity column after the weight.
+        // For all other graphs the edge weight doubles as the capacity.
+        let edges: Vec<(u32, u32, u32)> = match &benchmark.graph_data {
+            GraphBenchmarkData::RealWorldGraph { path_to_edge_list, weight_mode: WeightMode::UseFile } => {
+                GraphLoader::default()
+                    .load_capacitated_graph(path_to_edge_list)
+                    .unwrap_or_else(|error| panic!("{}", error))
+                    .into_iter()
+                    .map(|(from, to, _w, capacity)| (from, to, capacity))
+                    .collect()
+            }
+            graph_data => GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric).gen_initial_graph(graph_data),
+        };
+        println!("Computing max flow on {} edges:", edges.len());
+        ResidualGraph::from_edges(edges.into_iter().flat_map(|(from, to, capacity)| vec![(from, to, capacity), (to, from, 0)]))
+    });
+
+    let source = benchmark.search_query.source;
+    let target = benchmark.search_query.target;
+    for (name, node) in [("Source", source), ("Target", target)].iter() {
+        if *node as usize >= graph.node_count() {
+            panic!("{} node {} is not in the graph, valid node indices are in the range 0..{}", name, node, graph.node_count());
+        }
+    }
+    let flow = timer.time_subevent("Initial", || {
+        edmonds_karp(&mut graph, NodeIndex::new(source as usize), NodeIndex::new(target as usize))
+    });
+    println!(
+        "petgraph Edmonds-Karp algorithm finished in: {:?}",
+        timer.elapsed()
+    );
+    println!("Max flow from {} to {} is {}", source, target, flow);
+    if let Some(path) = &benchmark.trace_output {
+        timer.write_chrome_trace(path).expect("Could not write trace file");
+    }
+}
+
+fn paired_edge(edge: EdgeIndex<u32>) -> EdgeIndex<u32> {
+    EdgeIndex::new(edge.index() ^ 1)
+}
+
+/// Saturates augmenting paths found by breadth first search until none remain.
+/// Leaves the residual capacities in the graph and returns the total flow.
+fn edmonds_karp(graph: &mut ResidualGraph, source: NodeIndex<u32>, target: NodeIndex<u32>) -> u64 {
+    let mut flow = 0u64;
+    if source == target {
+        return flow;
+    }
+    loop {
+        // Breadth first search over edges with remaining capacity.
+        let mut parent_edge: Vec<Option<EdgeIndex<u32>>> = vec![None; graph.node_count()];
+        let mut visited = vec![false; graph.node_count()];
+        visited[source.index()] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            if node == target {
+                break;
+            }
+            for edge in graph.edges(node) {
+                let next = edge.target();
+                if *edge.weight() > 0 && !visited[next.index()] {
+                    visited[next.index()] = true;
+                    parent_edge[next.index()] = Some(edge.id());
+                    queue.push_back(next);
+                }
+            }
+        }
+        if !visited[target.index()] {
+            return flow;
+        }
+
+        // Push the bottleneck capacity along the path found.
+        let mut bottleneck = u32::max_value();
+        let mut node = target;
+        while let Some(edge) = parent_edge[node.index()] {
+            bottleneck = std::cmp::min(bottleneck, graph[edge]);
+            node = graph.edge_endpoints(edge).expect("Edge on path must exist").0;
+        }
+        let mut node = target;
+        while let Some(edge) = parent_edge[node.index()] {
+            graph[edge] -= bottleneck;
+            graph[paired_edge(edge)] += bottleneck;
+            node = graph.edge_endpoints(edge).expect("Edge on path must exist").0;
+        }
+        flow += bottleneck as u64;
+    }
+}
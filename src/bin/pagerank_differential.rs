@@ -0,0 +1,186 @@
+// PageRank in differential dataflow
+//
+// Computes a fixed number of PageRank iterations with damping factor 0.85, where the ranks
+// average to 1 over the nodes. Ranks are kept in fixed point, and the rank of nodes
+// without outgoing edges is not passed on. Each update round inserts new edges.
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::timely_arguments;
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+
+use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::operators::Filter;
+use timely::dataflow::*;
+use timely::order::Product;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::iterate::Variable;
+use differential_dataflow::operators::*;
+use differential_dataflow::AsCollection;
+use differential_dataflow::Collection;
+
+type Node = u32;
+type Edge = (Node, Node);
+type Rank = u64;
+
+/// Fixed point rank of 1.
+const RANK_SCALE: Rank = 1_000_000;
+/// Damping factor as the fraction `DAMPING_NUMERATOR / DAMPING_DENOMINATOR`.
+const DAMPING_NUMERATOR: Rank = 85;
+const DAMPING_DENOMINATOR: Rank = 100;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let inspect: bool = benchmark.inspect_results;
+    let target = benchmark.search_query.target;
+    let iterations = benchmark.iterations;
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        let mut probe = Handle::new();
+        let mut graph_in = worker.dataflow(|scope| {
+            let (edge_input, graph) = scope.new_collection();
+            let mut ranks = pagerank(&graph, iterations);
+
+            if inspect {
+                ranks = ranks.filter(move |(n, _)| *n == target);
+            } else {
+                ranks = ranks.filter(|_| false);
+            }
+
+            ranks
+                .map(|(_, rank)| rank)
+                .consolidate()
+                .inspect(|(rank, time, diff)| {
+                    println!("Target rank: {:?}", (*rank as f64 / RANK_SCALE as f64, time, diff))
+                })
+                .probe_with(&mut probe);
+
+            edge_input
+        });
+
+        // Random generator engine.
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        if worker_index == 0 {
+            timer.time_subevent("Loading", || {
+                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                println!(
+                    "Performing PageRank on {} nodes, {} edges:",
+                    gen.max_num_nodes(),
+                    initial_edges.len()
+                );
+                // Update data only on one worker.
+                for (from, to, _) in initial_edges.into_iter() {
+                    graph_in.update_at((from, to), Default::default(), 1);
+                }
+            });
+        }
+        let mut initial_advance = || {
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+        };
+        if worker_index == 0 {
+            timer.time_subevent("Initial", initial_advance);
+        } else {
+            initial_advance();
+        }
+
+        // Warmup rounds run first, through the same update path, but are neither timed nor reported.
+        let warmup_rounds = benchmark.warmup_rounds;
+        let num_rounds = benchmark.num_rounds;
+        let rounds_per_batch = benchmark.rounds_per_batch;
+        for round in 0..warmup_rounds + num_rounds {
+            if worker.index() == 0 {
+                // Insert elements for update
+                for (from, to, _) in gen.gen_graph_updates(&benchmark.graph_updates).into_iter() {
+                    graph_in.update_at((from, to), 1 + round, 1);
+                }
+            }
+            if !benchmark.ends_batch(round) {
+                // The changes stay at their round's time until the batch is complete.
+                continue;
+            }
+            graph_in.advance_to(2 + round);
+            // Flush to input to make sure all changes are in the message queues.
+            graph_in.flush();
+            let mut update_advance = || {
+                worker.step_while(|| probe.less_than(&graph_in.time()));
+            };
+            if round < warmup_rounds {
+                update_advance();
+            } else if worker_index == 0 {
+                timer.time_subevent(&format!("N {}", (round - warmup_rounds) / rounds_per_batch), update_advance);
+            } else {
+                update_advance();
+            }
+        }
+
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
+
+// returns pairs (n, r) indicating node n has rank r after the given number of iterations.
+fn pagerank<G: Scope>(
+    edges: &Collection<G, Edge>,
+    iterations: u32,
+) -> Collection<G, (Node, Rank)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    let nodes = edges.flat_map(|(from, to)| vec![from, to]).distinct();
+    let degrees = edges.map(|(from, _)| from).count();
+    // Every node keeps the undamped part of the rank on top of what it receives.
+    let reset = RANK_SCALE * (DAMPING_DENOMINATOR - DAMPING_NUMERATOR) / DAMPING_DENOMINATOR;
+
+    edges.scope().iterative::<u32, _, _>(|scope| {
+        let edges = edges.enter(scope);
+        let nodes = nodes.enter(scope);
+        let degrees = degrees.enter(scope);
+
+        // Every node starts with a rank of 1.
+        let ranks = Variable::new_from(nodes.map(|node| (node, RANK_SCALE)), Product::new(Default::default(), 1));
+
+        // Each node passes the damped rank on in equal shares along its edges.
+        let shares = ranks
+            .join_map(&degrees, |node, rank, degree| {
+                (*node, rank * DAMPING_NUMERATOR / (DAMPING_DENOMINATOR * *degree as Rank))
+            })
+            .join_map(&edges, |_from, share, to| (*to, *share));
+
+        let next = shares
+            // Nodes without incoming edges still keep the reset rank.
+            .concat(&nodes.map(|node| (node, 0)))
+            .reduce(move |_node, input, output| {
+                let received: Rank = input.iter().map(|(share, count)| **share * *count as Rank).sum();
+                output.push((reset + received, 1));
+            })
+            .inner
+            .filter(move |(_, time, _)| time.inner < iterations)
+            .as_collection();
+
+        ranks.set(&next);
+        next.leave()
+    })
+}
@@ -0,0 +1,119 @@
+/// Baseline implementation of a bidirectional Dijkstra search for the single query of the
+/// benchmark. A forward search from the source and a backward search from the target on the
+/// reversed edges take turns, and stop once no path through their frontiers can be shorter
+/// than the shortest path found where they meet.
+
+extern crate graph_utility;
+
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::adjacency_list;
+use graph_utility::{Node, Weight};
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Measure data loading.
+    let (forward, backward) = timer.time_subevent("Loading", || {
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+        println!(
+            "Performing SSSP on {} nodes, {} edges:",
+            gen.max_num_nodes(),
+            initial_edges.len()
+        );
+        let reversed_edges = initial_edges.iter().map(|(from, to, weight)| (*to, *from, *weight)).collect::<Vec<_>>();
+        (adjacency_list(&initial_edges, gen.max_num_nodes()), adjacency_list(&reversed_edges, gen.max_num_nodes()))
+    });
+    let query = &benchmark.search_query;
+    let num_nodes = forward.len();
+    let nodes = query.sources.iter().map(|source| ("Source", *source)).chain(Some(("Target", query.target)));
+    for (name, node) in nodes {
+        if node as usize >= num_nodes {
+            panic!("{} node {} is not in the graph, valid node indices are in the range 0..{}", name, node, num_nodes);
+        }
+    }
+    let (distance, settled) = timer.time_subevent("Initial", || {
+        bidirectional_dijkstra(&forward, &backward, &query.sources, query.target)
+    });
+    println!(
+        "Bidirectional Dijkstra algorithm finished in: {:?}, settled {} nodes",
+        timer.elapsed(),
+        settled
+    );
+    let sources = query.sources.iter().map(|source| source.to_string()).collect::<Vec<_>>().join(",");
+    match distance {
+        Some(distance) => println!("Cost from {} to {} is {}", sources, query.target, distance),
+        None => println!("Cost from {} to {} is inf", sources, query.target),
+    }
+    if let Some(path) = &benchmark.trace_output {
+        timer.write_chrome_trace(path).expect("Could not write trace file");
+    }
+}
+
+/// Distance from the nearest of the `sources` to `target`, or `None` if it is unreachable,
+/// and the number of nodes settled by both searches together.
+fn bidirectional_dijkstra(
+    forward: &[Vec<(Node, Weight)>],
+    backward: &[Vec<(Node, Weight)>],
+    sources: &[Node],
+    target: Node,
+) -> (Option<u64>, usize) {
+    // Index 0 is the forward search and index 1 the backward search.
+    let adjacency = [forward, backward];
+    let mut distances = [vec![None; forward.len()], vec![None; forward.len()]];
+    let mut heaps = [BinaryHeap::new(), BinaryHeap::new()];
+    for source in sources.iter() {
+        distances[0][*source as usize] = Some(0u64);
+        heaps[0].push(Reverse((0u64, *source)));
+    }
+    distances[1][target as usize] = Some(0u64);
+    heaps[1].push(Reverse((0u64, target)));
+    // Shortest path found so far through a node reached by both searches.
+    let mut best = distances[0][target as usize];
+    let mut settled = 0;
+
+    loop {
+        // Drop stale entries of nodes that were already reached by a shorter path.
+        for side in 0..2 {
+            while let Some(Reverse((distance, node))) = heaps[side].peek().cloned() {
+                if distances[side][node as usize].map_or(false, |known| distance > known) {
+                    heaps[side].pop();
+                } else {
+                    break;
+                }
+            }
+        }
+        // Once either search is exhausted, its distances are final and `best` is exact.
+        let (forward_top, backward_top) = match (heaps[0].peek(), heaps[1].peek()) {
+            (Some(Reverse((forward_top, _))), Some(Reverse((backward_top, _)))) => (*forward_top, *backward_top),
+            _ => break,
+        };
+        if best.map_or(false, |best| forward_top + backward_top >= best) {
+            break;
+        }
+        let side = if forward_top <= backward_top { 0 } else { 1 };
+        let Reverse((distance, node)) = heaps[side].pop().expect("Heap has a top");
+        settled += 1;
+        for (to, weight) in adjacency[side][node as usize].iter() {
+            let candidate = distance + *weight as u64;
+            if distances[side][*to as usize].map_or(true, |known| candidate < known) {
+                distances[side][*to as usize] = Some(candidate);
+                heaps[side].push(Reverse((candidate, *to)));
+                if let Some(other) = distances[1 - side][*to as usize] {
+                    best = Some(best.map_or(candidate + other, |best| std::cmp::min(best, candidate + other)));
+                }
+            }
+        }
+    }
+    (best, settled)
+}
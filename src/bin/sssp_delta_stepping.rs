@@ -0,0 +1,117 @@
+/// Baseline implementation of the delta-stepping algorithm on an adjacency list. Nodes are
+/// kept in buckets of distances `delta` wide, and the nodes of the smallest bucket are settled
+/// together, first relaxing the light edges of at most `delta` until the bucket stays empty,
+/// then the heavy ones. Runs sequentially, as a comparison point between Dijkstra and
+/// Bellman-Ford.
+
+extern crate graph_utility;
+
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::adjacency_list;
+use graph_utility::{Node, Weight};
+
+use std::collections::BTreeMap;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Measure data loading.
+    let adjacency = timer.time_subevent("Loading", || {
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+        println!(
+            "Performing SSSP on {} nodes, {} edges:",
+            gen.max_num_nodes(),
+            initial_edges.len()
+        );
+        adjacency_list(&initial_edges, gen.max_num_nodes())
+    });
+    let query = &benchmark.search_query;
+    let num_nodes = adjacency.len();
+    let target = if query.all_targets { None } else { Some(("Target", query.target)) };
+    let nodes = query.sources.iter().map(|source| ("Source", *source)).chain(target);
+    for (name, node) in nodes {
+        if node as usize >= num_nodes {
+            panic!("{} node {} is not in the graph, valid node indices are in the range 0..{}", name, node, num_nodes);
+        }
+    }
+    // Without a given width, a bucket spans the heaviest edge, so all edges are light.
+    let max_weight = adjacency.iter().flat_map(|edges| edges.iter().map(|(_, weight)| *weight)).max().unwrap_or(1);
+    let delta = benchmark.delta.unwrap_or(std::cmp::max(1, max_weight));
+    println!("Bucket width: {}", delta);
+    let distances = timer.time_subevent("Initial", || delta_stepping(&adjacency, &query.sources, delta));
+    println!(
+        "Delta-stepping algorithm finished in: {:?}",
+        timer.elapsed()
+    );
+    let sources = query.sources.iter().map(|source| source.to_string()).collect::<Vec<_>>().join(",");
+    if query.all_targets {
+        let reached = distances.iter().filter_map(|distance| *distance);
+        let (count, max_distance) = reached.fold((0, 0), |(count, max), distance| (count + 1, std::cmp::max(max, distance)));
+        println!("Reached {} nodes from {}, largest distance {}", count, sources, max_distance);
+    } else {
+        match distances[query.target as usize] {
+            Some(distance) => println!("Cost from {} to {} is {}", sources, query.target, distance),
+            None => println!("Cost from {} to {} is inf", sources, query.target),
+        }
+    }
+    if let Some(path) = &benchmark.trace_output {
+        timer.write_chrome_trace(path).expect("Could not write trace file");
+    }
+}
+
+/// Distance of every node from the nearest of the `sources`, or `None` for unreachable nodes.
+fn delta_stepping(adjacency: &[Vec<(Node, Weight)>], sources: &[Node], delta: Weight) -> Vec<Option<u64>> {
+    let delta = delta as u64;
+    let mut distances: Vec<Option<u64>> = vec![None; adjacency.len()];
+    // Nodes by the index of their bucket. A node moved to a lower bucket stays behind in
+    // the old one, and is skipped there as its distance no longer falls into it.
+    let mut buckets: BTreeMap<u64, Vec<Node>> = BTreeMap::new();
+    let relax = |distances: &mut Vec<Option<u64>>, buckets: &mut BTreeMap<u64, Vec<Node>>, node: Node, distance: u64| {
+        if distances[node as usize].map_or(true, |best| distance < best) {
+            distances[node as usize] = Some(distance);
+            buckets.entry(distance / delta).or_insert_with(Vec::new).push(node);
+        }
+    };
+    for source in sources.iter() {
+        relax(&mut distances, &mut buckets, *source, 0);
+    }
+
+    while let Some(index) = buckets.keys().next().cloned() {
+        let mut settled = Vec::new();
+        // Light edges can put nodes back into the current bucket.
+        while let Some(nodes) = buckets.remove(&index) {
+            for node in nodes.into_iter() {
+                let distance = distances[node as usize].expect("Nodes in buckets are reached");
+                if distance / delta != index {
+                    continue;
+                }
+                for (to, weight) in adjacency[node as usize].iter() {
+                    if (*weight as u64) <= delta {
+                        relax(&mut distances, &mut buckets, *to, distance + *weight as u64);
+                    }
+                }
+                settled.push(node);
+            }
+        }
+        // Heavy edges always lead to later buckets.
+        settled.sort();
+        settled.dedup();
+        for node in settled.into_iter() {
+            let distance = distances[node as usize].expect("Settled nodes are reached");
+            for (to, weight) in adjacency[node as usize].iter() {
+                if (*weight as u64) > delta {
+                    relax(&mut distances, &mut buckets, *to, distance + *weight as u64);
+                }
+            }
+        }
+    }
+    distances
+}
@@ -5,17 +5,41 @@ extern crate graph_utility;
 extern crate timely;
 
 use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::build_spanner;
+use graph_utility::induced_subgraph_by_attribute;
+use graph_utility::GraphLoader;
+use graph_utility::shortest_path_usage;
+use graph_utility::BenchmarkDescription;
+use graph_utility::GraphBenchmarkUpdates;
 use graph_utility::GraphDataGenerator;
 use graph_utility::SubEventTimer;
+use graph_utility::workers::{accumulate_on_worker_zero, gen_initial_graph_parallel};
+use graph_utility::timely_arguments;
+use graph_utility::write_dataflow_dot;
+use graph_utility::to_dot;
+use graph_utility::write_binary_graph;
+use graph_utility::write_dense_distances;
+use graph_utility::validate_distances;
+use graph_utility::sssp::{closest_nodes, reached_summary, reconstruct_path, run_update_rounds, sssp, sssp_with_predecessors, sssp_with_snapshots};
+use graph_utility::sssp::{IterationRecord, UpdateRounds};
 
 use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::operators::{Exchange, Inspect, Probe};
 use timely::dataflow::*;
+use timely::logging::TimelyEvent;
+use timely::communication::Allocate;
+use timely::worker::Worker;
 
 use differential_dataflow::input::Input;
 use differential_dataflow::lattice::Lattice;
 use differential_dataflow::operators::*;
 use differential_dataflow::Collection;
 
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
 type Node = u32;
 type Weight = u32;
 type Edge = (Node, Node, Weight);
@@ -25,42 +49,216 @@ fn main() {
     let benchmark = parse_graph_benchmark_arguments(std::env::args());
     let inspect: bool = benchmark.inspect_results;
     let target = benchmark.search_query.target;
+    let all_targets = benchmark.search_query.all_targets;
+    // Adversarial updates start from as many of the nodes closest to the source as there are new edges.
+    let num_near_nodes = match benchmark.graph_updates {
+        GraphBenchmarkUpdates::AdversarialUpdates { edges_per_update, .. } => std::cmp::max(1, edges_per_update as usize),
+        GraphBenchmarkUpdates::RandomUpdates { .. } => 0,
+    };
+    let adversarial = num_near_nodes > 0;
+    // Deletions are drawn from the edges in the graph, which the generator then keeps track of.
+    let deletions = match benchmark.graph_updates {
+        GraphBenchmarkUpdates::RandomUpdates { deletion_ratio, .. } => deletion_ratio > 0.0,
+        GraphBenchmarkUpdates::AdversarialUpdates { .. } => false,
+    };
+    // Congestion feedback reads the distances back to find the edges on shortest paths.
+    let congestion = benchmark.congestion_factor.is_some();
+    if benchmark.check_distances && benchmark.budget.is_some() {
+        panic!("--check-distances cannot be combined with --budget, which prunes the distances");
+    }
+    if all_targets && (benchmark.budget.is_some() || benchmark.reconstruct_path) {
+        panic!("The target all cannot be combined with --budget or --reconstruct-path, which report on a single target");
+    }
+    if benchmark.reconstruct_path && (benchmark.budget.is_some() || benchmark.iteration_snapshots.is_some() || benchmark.convergence_tail) {
+        panic!("--reconstruct-path cannot be combined with --budget, --iteration-snapshots or --convergence-tail");
+    }
+    if benchmark.parallel_loading && (deletions || congestion || benchmark.check_distances || benchmark.save_graph.is_some()
+        || benchmark.compare_recompute || benchmark.node_attributes.is_some() || benchmark.spanner_stretch.is_some()
+        || benchmark.graph_dot.is_some()) {
+        panic!("--parallel-loading cannot be combined with options that need the whole graph on worker 0: --deletion-ratio, --congestion-factor, --check-distances, --save-graph, --compare-recompute, --node-attributes, --spanner-stretch or --graph-dot");
+    }
     // Start timer.
-    let timer = SubEventTimer::new_timer();
+    let timer = SubEventTimer::new_recording().with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
 
     // Computation context definition.
-    timely::execute_from_args(std::env::args(), move |worker| {
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
         let worker_index = worker.index();
         // define BFS dataflow; return handles to roots and edges inputs
         let mut probe = Handle::new();
+        // Number of records sent between operators, counted from timely's message log.
+        let records = Rc::new(Cell::new(0));
+        // Operators and channels of the dataflow graph, as reported by timely's log.
+        let dataflow_graph = Rc::new(RefCell::new((Vec::new(), Vec::new())));
+        if benchmark.count_records || benchmark.dataflow_dot.is_some() {
+            let records = records.clone();
+            let dataflow_graph = dataflow_graph.clone();
+            worker
+                .log_register()
+                .insert::<TimelyEvent, _>("timely", move |_time, data| {
+                    for (_, _, event) in data.iter() {
+                        match event {
+                            TimelyEvent::Messages(message) if message.is_send => {
+                                records.set(records.get() + message.length);
+                            }
+                            TimelyEvent::Operates(operator) => {
+                                dataflow_graph.borrow_mut().0.push((operator.addr.clone(), operator.name.clone()));
+                            }
+                            TimelyEvent::Channels(channel) => {
+                                // Index 0 of a scope refers to the boundary of the scope itself.
+                                let endpoint = |index: usize| {
+                                    let mut addr = channel.scope_addr.clone();
+                                    if index > 0 {
+                                        addr.push(index);
+                                    }
+                                    addr
+                                };
+                                dataflow_graph.borrow_mut().1.push((endpoint(channel.source.0), endpoint(channel.target.0)));
+                            }
+                            _ => {}
+                        }
+                    }
+                });
+        }
+        // Accumulated (node, distance) updates gathered on worker 0 for the dense output,
+        // for finding the nodes closest to the source for adversarial updates, for
+        // finding the congested edges and for validation.
+        let distances = Rc::new(RefCell::new(HashMap::new()));
+        // Accumulated distances of the target gathered on worker 0, for reporting its
+        // distance after every round, or whether it is reachable within the budget.
+        let target_distances = Rc::new(RefCell::new(HashMap::new()));
+        // Changes of the distances at every iteration, gathered on worker 0 for the snapshots
+        // and the convergence tail.
+        let iteration_records = Rc::new(RefCell::new(Vec::new()));
+        let record_iterations = benchmark.iteration_snapshots.is_some() || benchmark.convergence_tail;
+        let snapshots = if record_iterations { Some(iteration_records.clone()) } else { None };
+        // Changes of the result since the last report, gathered on worker 0.
+        let result_changes = Rc::new(RefCell::new(Vec::new()));
+        // Accumulated (node, (distance, predecessor)) updates gathered on worker 0, for
+        // reconstructing the path to the target to print or draw it.
+        let predecessors = Rc::new(RefCell::new(HashMap::new()));
         let (mut roots, mut graph_in) = worker.dataflow(|scope| {
             let (root_input, roots) = scope.new_collection();
             let (edge_input, graph) = scope.new_collection();
-            let mut result = sssp(&graph, &roots);
+            let result = match benchmark.budget {
+                Some(budget) => sssp_within_budget(&graph, &roots, budget),
+                None if benchmark.reconstruct_path => {
+                    let result = sssp_with_predecessors(&graph, &roots);
+                    if inspect || benchmark.graph_dot.is_some() {
+                        accumulate_on_worker_zero(&result, predecessors.clone()).probe_with(&mut probe);
+                    }
+                    result.map(|(node, (distance, _))| (node, distance))
+                }
+                None => match snapshots {
+                    Some(snapshots) => sssp_with_snapshots(&graph, &roots, snapshots),
+                    None => sssp(&graph, &roots),
+                },
+            };
 
-            if inspect {
-                result = result.filter(move |(n, _)| *n == target);
-            } else {
-                result = result.filter(|_| false);
+            if benchmark.round_changes.is_some() {
+                let result_changes = result_changes.clone();
+                result
+                    .inner
+                    .exchange(|_| 0)
+                    .inspect(move |((node, distance), _time, diff)| {
+                        result_changes.borrow_mut().push((*node, *distance, *diff));
+                    })
+                    .probe_with(&mut probe);
+            }
+
+            if inspect && !all_targets {
+                let target_distance = result.filter(move |(n, _)| *n == target).map(|(_, distance)| distance);
+                accumulate_on_worker_zero(&target_distance, target_distances.clone()).probe_with(&mut probe);
             }
 
-            result
-                .map(|(_, l)| l)
-                .consolidate()
-                .inspect(|x| println!("Target node: {:?}", x))
-                .probe_with(&mut probe);
+            if benchmark.count_reachable {
+                // One record per reached node, counted under a single key.
+                result
+                    .map(|_| ())
+                    .count()
+                    .inspect(|(((), count), time, diff)| {
+                        if *diff > 0 {
+                            println!("Reachable nodes at {:?}: {}", time, count);
+                        }
+                    })
+                    .probe_with(&mut probe);
+            }
+
+            if benchmark.dense_out.is_some() || adversarial || congestion || benchmark.check_distances {
+                accumulate_on_worker_zero(&result, distances.clone()).probe_with(&mut probe);
+            }
+
+            if inspect && all_targets {
+                reached_summary(&result)
+                    .inspect(|((count, max_distance), time, diff)| {
+                        if *diff > 0 {
+                            println!("All targets at {:?}: {} reached nodes, largest distance {}", time, count, max_distance);
+                        }
+                    })
+                    .probe_with(&mut probe);
+            }
+
+            // The target distance is reported once the probe has passed each round,
+            // from the distances gathered above.
+            result.probe_with(&mut probe);
 
             (root_input, edge_input)
         });
-        let source = benchmark.search_query.source;
-        roots.insert(source);
+        if let Some(path) = &benchmark.dataflow_dot {
+            worker.log_register().flush();
+            if worker_index == 0 {
+                let (operators, channels) = &*dataflow_graph.borrow();
+                write_dataflow_dot(path, operators, channels).expect("Could not write dataflow graph file");
+            }
+        }
+        let sources = &benchmark.search_query.sources;
+        for source in sources.iter() {
+            roots.insert(*source);
+        }
         roots.close();
 
         // Random generator engine.
-        let mut gen = GraphDataGenerator::new_from_seed(10);
-        if worker_index == 0 {
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric).with_history(deletions);
+        // Multiplicity of each edge inserted by worker 0, kept for saving the final graph,
+        // for recomputing from scratch and for adjusting weights under congestion.
+        let mut graph_edges: HashMap<Edge, isize> = HashMap::new();
+        let track_graph = benchmark.save_graph.is_some() || benchmark.compare_recompute || congestion || benchmark.check_distances
+            || benchmark.graph_dot.is_some();
+        if benchmark.parallel_loading {
+            // Every worker loads and inserts its own share of the initial graph.
+            let mut load = || {
+                let initial_edges = gen_initial_graph_parallel(worker, &mut gen, &benchmark.graph_data)
+                    .unwrap_or_else(|error| panic!("{}", error));
+                if worker_index == 0 {
+                    println!(
+                        "Performing SSSP on {} nodes, {} edges:",
+                        gen.max_num_nodes(),
+                        gen.total_edges_emitted()
+                    );
+                }
+                for edge in initial_edges.iter() {
+                    graph_in.update_at(*edge, Default::default(), 1);
+                }
+            };
+            if worker_index == 0 {
+                timer.time_subevent("Loading", load);
+            } else {
+                load();
+            }
+        } else if worker_index == 0 {
             timer.time_subevent("Loading", || {
-                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                let mut initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                if let (Some(path), Some(values)) = (&benchmark.node_attributes, &benchmark.attribute_values) {
+                    let attributes = GraphLoader::default().load_node_attributes(path)
+                        .unwrap_or_else(|error| panic!("{}", error));
+                    initial_edges = induced_subgraph_by_attribute(initial_edges, &attributes, values);
+                }
+                if let Some(stretch) = benchmark.spanner_stretch {
+                    initial_edges = build_spanner(&initial_edges, stretch);
+                }
+                if deletions {
+                    gen.set_present_edges(initial_edges.clone());
+                }
+                gen.set_total_edges(initial_edges.len());
                 println!(
                     "Performing SSSP on {} nodes, {} edges:",
                     gen.max_num_nodes(),
@@ -69,6 +267,9 @@ fn main() {
                 // Update data only on one worker.
                 for edge in initial_edges.iter() {
                     graph_in.update_at(*edge, Default::default(), 1);
+                    if track_graph {
+                        *graph_edges.entry(*edge).or_insert(0) += 1;
+                    }
                 }
             });
         }
@@ -82,55 +283,382 @@ fn main() {
         } else {
             initial_advance();
         }
-
-        let num_rounds = benchmark.num_rounds;
-        for round in 0..num_rounds {
-            if worker.index() == 0 {
-                let batch_edges = gen.gen_graph_updates(&benchmark.graph_updates);
-                // Insert elements for update
-                for edge in batch_edges.into_iter() {
-                    graph_in.update_at(edge, 1 + round, -1);
-                }
-            }
-            graph_in.advance_to(2 + round);
-            // Flush to input to make sure all changes are in the message queues.
-            graph_in.flush();
-            let mut update_advance = || {
-                worker.step_while(|| probe.less_than(&graph_in.time()));
-            };
-            if worker_index == 0 {
-                timer.time_subevent(&format!("N {}", round), update_advance);
-            } else {
-                update_advance();
-            }
+        if benchmark.count_records {
+            worker.log_register().flush();
+            println!("Worker {} records: {:10}{}", worker_index, "Initial", records.replace(0));
+        }
+        // Only the measured rounds count towards the update throughput.
+        let initial_edges = gen.total_edges_emitted();
+        let warmup_update_edges = gen.update_edges_emitted();
+        let mut rounds = SsspRounds {
+            benchmark: &benchmark,
+            timer: &timer,
+            gen: gen,
+            graph_edges: graph_edges,
+            track_graph: track_graph,
+            num_near_nodes: num_near_nodes,
+            distances: distances.clone(),
+            target_distances: target_distances.clone(),
+            result_changes: result_changes.clone(),
+            records: records.clone(),
+            warmup_update_edges: warmup_update_edges,
+        };
+        if inspect && !all_targets && worker_index == 0 {
+            rounds.report_target("Initial");
+        }
+        if worker_index == 0 {
+            rounds.report_changes("Initial");
         }
 
+        // Warmup rounds run first, through the same update path, but are neither timed nor reported.
+        run_update_rounds(worker, &benchmark, &mut graph_in, &probe, &mut rounds);
+        let SsspRounds { gen, graph_edges, warmup_update_edges, .. } = rounds;
+
         println!(
             "Worker {} finished in: {:?}",
             worker.index(),
             timer.elapsed()
         );
+        if worker_index == 0 {
+            if benchmark.num_rounds > 0 {
+                println!("Round latency: {}", timer.summary("N "));
+            }
+            println!("Throughput: {}", timer.throughput(initial_edges, gen.update_edges_emitted() - warmup_update_edges));
+            if let Some(path) = &benchmark.save_graph {
+                let mut edges = Vec::new();
+                for (edge, count) in graph_edges.iter() {
+                    for _ in 0..*count {
+                        edges.push(*edge);
+                    }
+                }
+                edges.sort();
+                write_binary_graph(&edges, path).expect("Could not write graph file");
+            }
+            if benchmark.convergence_tail {
+                for (round, iteration, changed) in changed_per_iteration(&iteration_records.borrow()).into_iter() {
+                    println!("Round {:5} iteration {:5} changed nodes {:10}", round, iteration, changed);
+                }
+            }
+            if let Some(prefix) = &benchmark.iteration_snapshots {
+                write_iteration_snapshots(prefix, &iteration_records.borrow())
+                    .expect("Could not write iteration snapshot files");
+            }
+            // The shortest path to the target, also highlighted in the graph DOT output.
+            let mut shortest_path = None;
+            if benchmark.reconstruct_path && (inspect || benchmark.graph_dot.is_some()) {
+                let predecessors = predecessors
+                    .borrow()
+                    .keys()
+                    .map(|(node, (distance, predecessor))| (*node, (*distance, *predecessor)))
+                    .collect::<HashMap<_, _>>();
+                shortest_path = reconstruct_path(&predecessors, sources, target);
+                if inspect {
+                    match &shortest_path {
+                        Some(path) => println!("Shortest path with weight {}: {:?}", predecessors[&target].0, path),
+                        None => println!("No path from {:?} to {}", sources, target),
+                    }
+                }
+            }
+            if let Some(path) = &benchmark.graph_dot {
+                let mut edges = graph_edges
+                    .iter()
+                    .flat_map(|(edge, count)| std::iter::repeat(*edge).take(std::cmp::max(0, *count) as usize))
+                    .collect::<Vec<_>>();
+                edges.sort();
+                std::fs::write(path, to_dot(&edges, shortest_path.as_ref().map(|path| path.as_slice())))
+                    .expect("Could not write graph DOT file");
+            }
+            if benchmark.check_distances {
+                let edges = graph_edges
+                    .iter()
+                    .flat_map(|(edge, count)| std::iter::repeat(*edge).take(std::cmp::max(0, *count) as usize))
+                    .collect::<Vec<_>>();
+                let distances = distances
+                    .borrow()
+                    .keys()
+                    .map(|(node, distance)| (*node, *distance as u64))
+                    .collect::<HashMap<_, _>>();
+                let compared = validate_distances(&edges, sources, &distances)
+                    .unwrap_or_else(|mismatches| panic!("{}", mismatches));
+                println!("Validated the distances of {} nodes against Dijkstra", compared);
+            }
+            if let Some(path) = &benchmark.dense_out {
+                let distances = distances.borrow().keys().cloned().collect::<Vec<_>>();
+                write_dense_distances(path, gen.max_num_nodes(), distances)
+                    .expect("Could not write dense distances file");
+            }
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
     })
     .unwrap();
 }
 
-fn sssp<G: Scope>(
+/// The update rounds of the benchmark on each worker, with the graph kept by worker 0 and
+/// the results gathered on it for congestion, adversarial updates and the reports.
+struct SsspRounds<'a> {
+    benchmark: &'a BenchmarkDescription,
+    timer: &'a SubEventTimer,
+    gen: GraphDataGenerator,
+    graph_edges: HashMap<Edge, isize>,
+    track_graph: bool,
+    num_near_nodes: usize,
+    distances: Rc<RefCell<HashMap<(Node, Weight), isize>>>,
+    target_distances: Rc<RefCell<HashMap<Weight, isize>>>,
+    result_changes: Rc<RefCell<Vec<(Node, Weight, isize)>>>,
+    records: Rc<Cell<usize>>,
+    /// Updates emitted before the first measured round.
+    warmup_update_edges: usize,
+}
+
+impl<'a> SsspRounds<'a> {
+    fn report_target(&self, event: &str) {
+        let target = self.benchmark.search_query.target;
+        let distance = self.target_distances.borrow().keys().min().cloned();
+        match (self.benchmark.budget, distance) {
+            (Some(budget), Some(distance)) => println!("{:10}Target {} is reachable within budget {} at distance {}", event, target, budget, distance),
+            (Some(budget), None) => println!("{:10}Target {} is not reachable within budget {}", event, target, budget),
+            (None, Some(distance)) => println!("{:10}Target {} at distance {}", event, target, distance),
+            (None, None) => println!("{:10}Target {} is unreachable", event, target),
+        }
+    }
+
+    fn report_changes(&self, event: &str) {
+        if let Some(limit) = self.benchmark.round_changes {
+            let changes = changed_distances(self.result_changes.replace(Vec::new()));
+            println!("{:10}{} nodes changed their distance", event, changes.len());
+            for (node, old, new) in changes.iter().take(limit) {
+                println!("{:10}Node {}: {:?} -> {:?}", event, node, old, new);
+            }
+            if changes.len() > limit {
+                println!("{:10}... and {} more", event, changes.len() - limit);
+            }
+        }
+    }
+}
+
+impl<'a, A: Allocate> UpdateRounds<A> for SsspRounds<'a> {
+    fn changes(&mut self, round: u32) -> Vec<(Edge, isize)> {
+        if round == self.benchmark.warmup_rounds {
+            // Only the measured rounds count towards the update throughput.
+            self.warmup_update_edges = self.gen.update_edges_emitted();
+        }
+        let changes = match self.benchmark.congestion_factor {
+            Some(factor) => {
+                // Each edge on a shortest path gets heavier in proportion to the number of
+                // nodes whose path uses it: retract it and insert it with the new weight.
+                let current = self.distances.borrow().keys().cloned().collect::<HashMap<Node, Weight>>();
+                let mut edges = self
+                    .graph_edges
+                    .iter()
+                    .filter(|(_, count)| **count > 0)
+                    .map(|(edge, _)| *edge)
+                    .collect::<Vec<_>>();
+                edges.sort();
+                let usage = shortest_path_usage(&edges, &current, &self.benchmark.search_query.sources);
+                let mut changes = Vec::new();
+                for (edge, paths) in edges.into_iter().zip(usage.into_iter()) {
+                    let increase = (factor * paths as f64).round() as Weight;
+                    if increase == 0 {
+                        continue;
+                    }
+                    let (from, to, weight) = edge;
+                    changes.push((edge, -1));
+                    changes.push(((from, to, weight.saturating_add(increase)), 1));
+                }
+                changes
+            }
+            // Shortcuts from the nodes closest to the source for adversarial updates.
+            None => {
+                let near_nodes = closest_nodes(&self.distances.borrow(), self.num_near_nodes);
+                self.gen.gen_graph_changes_near(&self.benchmark.graph_updates, &near_nodes)
+            }
+        };
+        if self.track_graph {
+            for (edge, diff) in changes.iter() {
+                *self.graph_edges.entry(*edge).or_insert(0) += *diff;
+            }
+        }
+        changes
+    }
+
+    fn measured_batch(&mut self, worker: &mut Worker<A>, batch: u32, wait: &mut dyn FnMut(&mut Worker<A>)) {
+        let worker_index = worker.index();
+        let round_timer = Instant::now();
+        if worker_index == 0 {
+            self.timer.time_subevent_with_edges(&format!("N {}", batch), self.gen.total_edges_emitted(), || wait(worker));
+        } else {
+            wait(worker);
+        }
+        if self.benchmark.compare_recompute {
+            let incremental = round_timer.elapsed();
+            let recompute = recompute_from_scratch(worker, &self.graph_edges, &self.benchmark.search_query.sources);
+            if worker_index == 0 {
+                println!(
+                    "Round {:5} incremental {:15} recompute {:15} speedup {:.2}",
+                    batch,
+                    format!("{:?}", incremental),
+                    format!("{:?}", recompute),
+                    recompute.as_secs_f64() / incremental.as_secs_f64()
+                );
+            }
+        }
+        if self.benchmark.count_records {
+            worker.log_register().flush();
+            println!("Worker {} records: {:10}{}", worker_index, format!("N {}", batch), self.records.replace(0));
+        }
+        if self.benchmark.inspect_results && !self.benchmark.search_query.all_targets && worker_index == 0 {
+            self.report_target(&format!("N {}", batch));
+        }
+        if worker_index == 0 {
+            self.report_changes(&format!("N {}", batch));
+        }
+    }
+
+    fn warmup_batch(&mut self, worker: &mut Worker<A>) {
+        if self.benchmark.count_records {
+            worker.log_register().flush();
+            self.records.replace(0);
+        }
+        self.result_changes.replace(Vec::new());
+    }
+}
+
+/// Run SSSP from scratch on a snapshot of the graph in a separate dataflow and return
+/// the time until it completes. Only worker 0 holds the snapshot edges.
+fn recompute_from_scratch<A: Allocate>(
+    worker: &mut Worker<A>,
+    edges: &HashMap<Edge, isize>,
+    sources: &[Node],
+) -> Duration {
+    let timer = Instant::now();
+    let mut probe = Handle::new();
+    let (mut roots, mut graph_in) = worker.dataflow::<u32, _, _>(|scope| {
+        let (root_input, roots) = scope.new_collection();
+        let (edge_input, graph) = scope.new_collection();
+        sssp(&graph, &roots).probe_with(&mut probe);
+        (root_input, edge_input)
+    });
+    for source in sources.iter() {
+        roots.insert(*source);
+    }
+    roots.close();
+    for (edge, count) in edges.iter() {
+        graph_in.update(*edge, *count);
+    }
+    graph_in.close();
+    worker.step_while(|| !probe.done());
+    timer.elapsed()
+}
+
+/// Group changes of the result into (node, old distance, new distance), sorted by node,
+/// where `None` means the node was not reached. Changes that cancel out are left out.
+fn changed_distances(changes: Vec<(Node, Weight, isize)>) -> Vec<(Node, Option<Weight>, Option<Weight>)> {
+    use std::collections::BTreeMap;
+
+    let mut net: HashMap<(Node, Weight), isize> = HashMap::new();
+    for (node, distance, diff) in changes.into_iter() {
+        *net.entry((node, distance)).or_insert(0) += diff;
+    }
+    let mut by_node: BTreeMap<Node, (Option<Weight>, Option<Weight>)> = BTreeMap::new();
+    for ((node, distance), diff) in net.into_iter() {
+        let entry = by_node.entry(node).or_insert((None, None));
+        if diff < 0 {
+            entry.0 = Some(distance);
+        } else if diff > 0 {
+            entry.1 = Some(distance);
+        }
+    }
+    by_node
+        .into_iter()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(node, (old, new))| (node, old, new))
+        .collect()
+}
+
+/// Number of nodes whose distance changed at each iteration of each round, as
+/// (round, iteration, nodes) sorted by round and iteration.
+fn changed_per_iteration(records: &[IterationRecord<u32>]) -> Vec<(u32, u64, usize)> {
+    use std::collections::{BTreeMap, HashSet};
+
+    // Net change of each (node, distance) at exactly each round and iteration.
+    let mut changes: BTreeMap<(u32, u64), HashMap<(Node, Weight), isize>> = BTreeMap::new();
+    for (node, distance, round, iteration, diff) in records.iter() {
+        *changes
+            .entry((*round, *iteration))
+            .or_insert_with(HashMap::new)
+            .entry((*node, *distance))
+            .or_insert(0) += *diff;
+    }
+    changes
+        .into_iter()
+        .map(|((round, iteration), diffs)| {
+            let nodes: HashSet<Node> = diffs
+                .into_iter()
+                .filter(|(_, diff)| *diff != 0)
+                .map(|((node, _), _)| node)
+                .collect();
+            (round, iteration, nodes.len())
+        })
+        .collect()
+}
+
+/// Write the distances at every iteration of every round to the file `<prefix>.<round>.<iteration>`,
+/// as lines of "node distance". The distances at an iteration accumulate all changes at
+/// earlier or equal rounds and iterations, as differential dataflow does.
+fn write_iteration_snapshots(prefix: &str, records: &[IterationRecord<u32>]) -> std::io::Result<()> {
+    use std::collections::BTreeMap;
+    use std::io::Write;
+
+    let mut last_iteration: BTreeMap<u32, u64> = BTreeMap::new();
+    for (_, _, round, iteration, _) in records.iter() {
+        let last = last_iteration.entry(*round).or_insert(0);
+        *last = std::cmp::max(*last, *iteration);
+    }
+    for (round, last) in last_iteration.into_iter() {
+        for iteration in 0..=last {
+            let mut distances: BTreeMap<(Node, Weight), isize> = BTreeMap::new();
+            for (node, distance, r, i, diff) in records.iter() {
+                if *r <= round && *i <= iteration {
+                    *distances.entry((*node, *distance)).or_insert(0) += *diff;
+                }
+            }
+            let path = format!("{}.{}.{}", prefix, round, iteration);
+            let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+            for ((node, distance), count) in distances.into_iter() {
+                if count > 0 {
+                    writeln!(file, "{} {}", node, distance)?;
+                }
+            }
+            file.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// SSSP that only follows paths with a total weight within `budget`. Nodes that can only
+/// be reached by longer paths are left out of the result.
+fn sssp_within_budget<G: Scope>(
     edges: &Collection<G, Edge>,
     roots: &Collection<G, Node>,
+    budget: Weight,
 ) -> Collection<G, (Node, Weight)>
 where
     G::Timestamp: Lattice + Ord,
 {
     // initialize roots as reaching themselves at distance 0
     let nodes = roots.map(|x| (x, 0));
-    // Repeatedly update minimal distances each node can be reached from each root
+    // Repeatedly update minimal distances, pruning relaxations that exceed the budget
     nodes.iterate(|inner| {
         let edges = edges
             .enter(&inner.scope())
             .map(|(from, to, w)| (from, (to, w)));
         let nodes = nodes.enter(&inner.scope());
         inner
-            .join_map(&edges, |_from, &cost, &(to, w)| (to, cost + w))
+            .join_map(&edges, |_from, &cost, &(to, w)| (to, cost as u64 + w as u64))
+            .filter(move |(_, cost)| *cost <= budget as u64)
+            .map(|(to, cost)| (to, cost as Weight))
             .concat(&nodes)
             // Note: reduce receives its input as an ordered collection.
             .reduce(|_, input, output| output.push((*input[0].0, 1)))
@@ -4,81 +4,134 @@ extern crate differential_dataflow;
 extern crate graph_utility;
 extern crate timely;
 
-#[macro_use]
-extern crate abomonation_derive;
-extern crate abomonation;
-#[macro_use]
-extern crate serde_derive;
-extern crate serde;
-
 use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::build_spanner;
+use graph_utility::induced_subgraph_by_attribute;
+use graph_utility::GraphLoader;
 use graph_utility::GraphDataGenerator;
 use graph_utility::SubEventTimer;
+use graph_utility::timely_arguments;
+use graph_utility::write_dataflow_dot;
+use graph_utility::validate_distances;
+use graph_utility::sssp::{reached_summary, sssp_monoid, sssp_monoid_bounded, sssp_monoid_u16, MinSum};
 
 use timely::dataflow::operators::probe::Handle;
-use timely::dataflow::*;
+use timely::dataflow::operators::{Exchange, Inspect, Probe};
+use timely::logging::TimelyEvent;
 
 use differential_dataflow::input::Input;
-use differential_dataflow::lattice::Lattice;
 use differential_dataflow::operators::*;
-use differential_dataflow::Collection;
-
-type Node = u32;
-type Edge = (Node, Node);
-type Weight = u32;
-
-#[derive(
-    Abomonation, Copy, Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash,
-)]
-pub struct MinSum {
-    value: Weight,
-}
-
-use differential_dataflow::difference::Semigroup;
-use std::ops::{AddAssign, Mul};
-
-impl<'a> AddAssign<&'a Self> for MinSum {
-    fn add_assign(&mut self, rhs: &'a Self) {
-        self.value = std::cmp::min(self.value, rhs.value);
-    }
-}
-
-impl Mul<Self> for MinSum {
-    type Output = Self;
-    fn mul(self, rhs: Self) -> Self {
-        MinSum {
-            value: self.value + rhs.value,
-        }
-    }
-}
 
-impl Semigroup for MinSum {
-    fn is_zero(&self) -> bool {
-        false
-    }
-}
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
 
 fn main() {
     // Parse arguments.
     let benchmark = parse_graph_benchmark_arguments(std::env::args());
     let inspect: bool = benchmark.inspect_results;
     let target = benchmark.search_query.target;
+    let all_targets = benchmark.search_query.all_targets;
+    if benchmark.check_distances && benchmark.max_distance.is_some() {
+        panic!("--check-distances cannot be combined with --max-distance, which drops the longer distances");
+    }
     // Start timer.
-    let timer = SubEventTimer::new_timer();
+    let timer = SubEventTimer::new_recording().with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
 
     // Define computation graph
-    timely::execute_from_args(std::env::args(), move |worker| {
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
         let worker_index = worker.index();
 
         // define BFS dataflow; return handles to roots and edges inputs
         let mut probe = Handle::new();
+        // Number of records sent between operators, counted from timely's message log.
+        let records = Rc::new(Cell::new(0));
+        // Operators and channels of the dataflow graph, as reported by timely's log.
+        let dataflow_graph = Rc::new(RefCell::new((Vec::new(), Vec::new())));
+        if benchmark.count_records || benchmark.dataflow_dot.is_some() {
+            let records = records.clone();
+            let dataflow_graph = dataflow_graph.clone();
+            worker
+                .log_register()
+                .insert::<TimelyEvent, _>("timely", move |_time, data| {
+                    for (_, _, event) in data.iter() {
+                        match event {
+                            TimelyEvent::Messages(message) if message.is_send => {
+                                records.set(records.get() + message.length);
+                            }
+                            TimelyEvent::Operates(operator) => {
+                                dataflow_graph.borrow_mut().0.push((operator.addr.clone(), operator.name.clone()));
+                            }
+                            TimelyEvent::Channels(channel) => {
+                                // Index 0 of a scope refers to the boundary of the scope itself.
+                                let endpoint = |index: usize| {
+                                    let mut addr = channel.scope_addr.clone();
+                                    if index > 0 {
+                                        addr.push(index);
+                                    }
+                                    addr
+                                };
+                                dataflow_graph.borrow_mut().1.push((endpoint(channel.source.0), endpoint(channel.target.0)));
+                            }
+                            _ => {}
+                        }
+                    }
+                });
+        }
+        // Distance of every node gathered on worker 0 for validation.
+        let distances = Rc::new(RefCell::new(HashMap::new()));
         let (mut roots, mut graph_in) = worker.dataflow(|scope| {
             let (root_input, roots) = scope.new_collection();
             let (edge_input, graph) = scope.new_collection();
 
-            let mut result = sssp_monoid(&graph, &roots);
+            let mut result = if benchmark.iteration_width == 16 {
+                sssp_monoid_u16(&graph, &roots, benchmark.max_distance)
+            } else if let Some(max_distance) = benchmark.max_distance {
+                sssp_monoid_bounded(&graph, &roots, max_distance)
+            } else {
+                sssp_monoid(&graph, &roots)
+            };
+
+            if benchmark.check_distances {
+                // The distance of a node accumulates as the minimum of all its updates.
+                let distances = distances.clone();
+                result
+                    .inner
+                    .exchange(|_| 0)
+                    .inspect(move |(node, _time, diff)| {
+                        let mut distances = distances.borrow_mut();
+                        let distance = distances.entry(*node).or_insert(diff.value);
+                        *distance = std::cmp::min(*distance, diff.value);
+                    })
+                    .probe_with(&mut probe);
+            }
+
+            if benchmark.count_reachable {
+                // One record per reached node, counted under a single key.
+                result
+                    .count()
+                    .map(|_| ())
+                    .count()
+                    .inspect(|(((), count), time, diff)| {
+                        if *diff > 0 {
+                            println!("Reachable nodes at {:?}: {}", time, count);
+                        }
+                    })
+                    .probe_with(&mut probe);
+            }
 
-            if inspect {
+            if inspect && all_targets {
+                // The distances as records, as the count of a node accumulates its distance.
+                let distances = result.count().map(|(node, distance)| (node, distance.value));
+                reached_summary(&distances)
+                    .inspect(|((count, max_distance), time, diff)| {
+                        if *diff > 0 {
+                            println!("All targets at {:?}: {} reached nodes, largest distance {}", time, count, max_distance);
+                        }
+                    })
+                    .probe_with(&mut probe);
+                result = result.filter(|_| false);
+            } else if inspect {
                 result = result.filter(move |n| *n == target);
             } else {
                 result = result.filter(|_| false);
@@ -92,16 +145,36 @@ fn main() {
 
             (root_input, edge_input)
         });
+        if let Some(path) = &benchmark.dataflow_dot {
+            worker.log_register().flush();
+            if worker_index == 0 {
+                let (operators, channels) = &*dataflow_graph.borrow();
+                write_dataflow_dot(path, operators, channels).expect("Could not write dataflow graph file");
+            }
+        }
 
-        let source = benchmark.search_query.source;
-        roots.update_at(source, Default::default(), MinSum { value: 0 });
+        let sources = &benchmark.search_query.sources;
+        for source in sources.iter() {
+            roots.update_at(*source, Default::default(), MinSum { value: 0 });
+        }
         roots.close();
 
         // Random generator engine.
-        let mut gen = GraphDataGenerator::new_from_seed(10);
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        // Edges inserted by worker 0, kept for validation.
+        let mut graph_edges = Vec::new();
         if worker_index == 0 {
             timer.time_subevent("Loading", || {
-                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                let mut initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                if let (Some(path), Some(values)) = (&benchmark.node_attributes, &benchmark.attribute_values) {
+                    let attributes = GraphLoader::default().load_node_attributes(path)
+                        .unwrap_or_else(|error| panic!("{}", error));
+                    initial_edges = induced_subgraph_by_attribute(initial_edges, &attributes, values);
+                }
+                if let Some(stretch) = benchmark.spanner_stretch {
+                    initial_edges = build_spanner(&initial_edges, stretch);
+                }
+                gen.set_total_edges(initial_edges.len());
                 println!(
                     "Performing SSSP on {} nodes, {} edges:",
                     gen.max_num_nodes(),
@@ -111,6 +184,9 @@ fn main() {
                 for (from, to, w) in initial_edges.iter() {
                     graph_in.update_at((*from, *to), Default::default(), MinSum { value: *w });
                 }
+                if benchmark.check_distances {
+                    graph_edges = initial_edges;
+                }
             });
         }
         let mut initial_advance = || {
@@ -123,27 +199,60 @@ fn main() {
         } else {
             initial_advance();
         }
+        if benchmark.count_records {
+            worker.log_register().flush();
+            println!("Worker {} records: {:10}{}", worker_index, "Initial", records.replace(0));
+        }
 
+        // Warmup rounds run first, through the same update path, but are neither timed nor reported.
+        let warmup_rounds = benchmark.warmup_rounds;
         let num_rounds = benchmark.num_rounds;
-        for round in 0..num_rounds {
+        let rounds_per_batch = benchmark.rounds_per_batch;
+        let initial_edges = gen.total_edges_emitted();
+        let mut warmup_update_edges = gen.update_edges_emitted();
+        for round in 0..warmup_rounds + num_rounds {
+            if round == warmup_rounds {
+                // Only the measured rounds count towards the update throughput.
+                warmup_update_edges = gen.update_edges_emitted();
+            }
             if worker.index() == 0 {
                 let batch_edges = gen.gen_graph_updates(&benchmark.graph_updates);
                 // Insert elements for update
                 for (from, to, _w) in batch_edges.into_iter() {
                     graph_in.update_at((from, to), 1 + round, MinSum { value: 1000 });
+                    if benchmark.check_distances {
+                        graph_edges.push((from, to, 1000));
+                    }
                 }
             }
+            if !benchmark.ends_batch(round) {
+                // The changes stay at their round's time until the batch is complete.
+                continue;
+            }
             graph_in.advance_to(2 + round);
             // Flush to input to make sure all changes are in the message queues.
             graph_in.flush();
             let mut update_advance = || {
                 worker.step_while(|| probe.less_than(&graph_in.time()));
             };
+            if round < warmup_rounds {
+                update_advance();
+                if benchmark.count_records {
+                    worker.log_register().flush();
+                    records.replace(0);
+                }
+                continue;
+            }
+            let round = (round - warmup_rounds) / rounds_per_batch;
             if worker_index == 0 {
-                timer.time_subevent(&format!("N {}", round), update_advance);
+                timer.time_subevent_with_edges(&format!("N {}", round), gen.total_edges_emitted(), update_advance);
             } else {
                 update_advance();
             }
+            if benchmark.count_records {
+                worker.log_register().flush();
+                println!("Worker {} records: {:10}{}", worker_index, format!("N {}", round), records.replace(0));
+            }
         }
 
         println!(
@@ -151,43 +260,25 @@ fn main() {
             worker.index(),
             timer.elapsed()
         );
+        if worker_index == 0 {
+            if num_rounds > 0 {
+                println!("Round latency: {}", timer.summary("N "));
+            }
+            println!("Throughput: {}", timer.throughput(initial_edges, gen.update_edges_emitted() - warmup_update_edges));
+            if benchmark.check_distances {
+                let distances = distances
+                    .borrow()
+                    .iter()
+                    .map(|(node, distance)| (*node, *distance as u64))
+                    .collect::<HashMap<_, _>>();
+                let compared = validate_distances(&graph_edges, sources, &distances)
+                    .unwrap_or_else(|mismatches| panic!("{}", mismatches));
+                println!("Validated the distances of {} nodes against Dijkstra", compared);
+            }
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
     })
     .unwrap();
 }
-
-// returns pairs (n, s) indicating node n can be reached from a root in s steps.
-fn sssp_monoid<G: Scope>(
-    edges: &Collection<G, Edge, MinSum>,
-    roots: &Collection<G, Node, MinSum>,
-) -> Collection<G, Node, MinSum>
-where
-    G::Timestamp: Lattice + Ord,
-{
-    // repeatedly update minimal distances each node can be reached from each root
-    roots.scope().iterative::<u32, _, _>(|scope| {
-        use differential_dataflow::operators::iterate::SemigroupVariable;
-        use differential_dataflow::operators::reduce::ReduceCore;
-        use differential_dataflow::trace::implementations::ord::OrdKeySpine as DefaultKeyTrace;
-
-        use timely::order::Product;
-        let variable = SemigroupVariable::new(scope, Product::new(Default::default(), 1));
-
-        let edges = edges.enter(scope);
-        let roots = roots.enter(scope);
-
-        let result = variable
-            .map(|n| (n, ()))
-            .join_map(&edges, |_k, &(), d| *d)
-            .concat(&roots)
-            .map(|x| (x, ()))
-            .reduce_core::<_, DefaultKeyTrace<_, _, _>>("Reduce", |_key, input, output, updates| {
-                if output.is_empty() || input[0].1 < output[0].1 {
-                    updates.push(((), input[0].1));
-                }
-            })
-            .as_collection(|k, ()| *k);
-
-        variable.set(&result);
-        result.leave()
-    })
-}
@@ -0,0 +1,59 @@
+/// Baseline implementation of Dijkstra's algorithm with a binary heap
+/// on an adjacency list. Assumes non-negative weights, as generated.
+
+extern crate graph_utility;
+
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::adjacency_list;
+use graph_utility::dijkstra;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Measure data loading.
+    let adjacency = timer.time_subevent("Loading", || {
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+        println!(
+            "Performing SSSP on {} nodes, {} edges:",
+            gen.max_num_nodes(),
+            initial_edges.len()
+        );
+        adjacency_list(&initial_edges, gen.max_num_nodes())
+    });
+    let query = &benchmark.search_query;
+    let num_nodes = adjacency.len();
+    let target = if query.all_targets { None } else { Some(("Target", query.target)) };
+    let nodes = query.sources.iter().map(|source| ("Source", *source)).chain(target);
+    for (name, node) in nodes {
+        if node as usize >= num_nodes {
+            panic!("{} node {} is not in the graph, valid node indices are in the range 0..{}", name, node, num_nodes);
+        }
+    }
+    let distances = timer.time_subevent("Initial", || dijkstra(&adjacency, &query.sources));
+    println!(
+        "Dijkstra algorithm finished in: {:?}",
+        timer.elapsed()
+    );
+    let sources = query.sources.iter().map(|source| source.to_string()).collect::<Vec<_>>().join(",");
+    if query.all_targets {
+        let reached = distances.iter().filter_map(|distance| *distance);
+        let (count, max_distance) = reached.fold((0, 0), |(count, max), distance| (count + 1, std::cmp::max(max, distance)));
+        println!("Reached {} nodes from {}, largest distance {}", count, sources, max_distance);
+    } else {
+        match distances[query.target as usize] {
+            Some(distance) => println!("Cost from {} to {} is {}", sources, query.target, distance),
+            None => println!("Cost from {} to {} is inf", sources, query.target),
+        }
+    }
+    if let Some(path) = &benchmark.trace_output {
+        timer.write_chrome_trace(path).expect("Could not write trace file");
+    }
+}
@@ -1,65 +1,140 @@
 /// Baseline implementation using the petgraph Rust graph
-/// library.
+/// library: Dijkstra's algorithm, or Bellman-Ford with `--signed-weights`.
+/// With `--undirected`, Dijkstra's algorithm runs on an undirected petgraph graph.
 
 extern crate graph_utility;
 extern crate petgraph;
 
+use graph_utility::BenchmarkDescription;
+use graph_utility::GraphBenchmarkData;
 use graph_utility::GraphDataGenerator;
 use graph_utility::SubEventTimer;
 use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::build_spanner;
+use graph_utility::induced_subgraph_by_attribute;
+use graph_utility::GraphLoader;
+use graph_utility::default_rng;
+use graph_utility::permute_node_ids;
+use graph_utility::Weight;
 
-use petgraph::algo::bellman_ford;
+use petgraph::algo::{bellman_ford, dijkstra};
 use petgraph::prelude::*;
+use petgraph::EdgeType;
 use petgraph::Graph;
-use petgraph::algo::FloatMeasure;
-
-/// Workaround as petgraph::algo::FloatMeasure is not implemented for u32 types.
-#[derive(Debug, PartialEq, PartialOrd, Clone, Copy, Default)]
-struct U32(u32);
-
-impl std::ops::Add for U32 {
-    type Output = Self;
-    fn add(self, other: Self) -> Self {
-        Self (self.0 + other.0)
-    }
-}
-
-impl FloatMeasure for U32 {
-    fn zero() -> Self { U32(0) }
-    fn infinite() -> Self { U32(u32::max_value()) }
-}
 
 fn main() {
     // Parse arguments.
     let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    if benchmark.search_query.sources.len() > 1 {
+        panic!("sssp_petgraph searches from a single source");
+    }
+    if benchmark.signed_weights && (benchmark.node_attributes.is_some() || benchmark.spanner_stretch.is_some() || benchmark.permute_nodes) {
+        panic!("--signed-weights cannot be combined with --node-attributes, --spanner-stretch or --permute-nodes");
+    }
 
     // Start timer.
-    let timer = SubEventTimer::new_timer();
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    if benchmark.signed_weights {
+        search_signed(&benchmark, &timer);
+    } else if benchmark.directed {
+        search::<Directed>(&benchmark, &timer);
+    } else {
+        search::<Undirected>(&benchmark, &timer);
+    }
+    if let Some(path) = &benchmark.trace_output {
+        timer.write_chrome_trace(path).expect("Could not write trace file");
+    }
+}
 
+/// Dijkstra's algorithm on the native non-negative integer weights. An undirected graph
+/// holds every edge once, where the dataflow binaries insert it in both directions.
+fn search<Ty: EdgeType>(benchmark: &BenchmarkDescription, timer: &SubEventTimer) {
     // Measure data loading.
-    let graph = timer.time_subevent("Loading", ||{
-        let mut gen = GraphDataGenerator::new_from_seed(10);
+    let (graph, mapping) = timer.time_subevent("Loading", ||{
+        // An undirected graph follows every edge both ways without its reverse.
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric && benchmark.directed);
         // Initial graph data.
-        let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+        let mut initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+        if let (Some(path), Some(values)) = (&benchmark.node_attributes, &benchmark.attribute_values) {
+            let attributes = GraphLoader::default().load_node_attributes(path)
+                .unwrap_or_else(|error| panic!("{}", error));
+            initial_edges = induced_subgraph_by_attribute(initial_edges, &attributes, values);
+        }
+        if let Some(stretch) = benchmark.spanner_stretch {
+            initial_edges = build_spanner(&initial_edges, stretch);
+        }
         println!(
             "Performing SSSP on {} nodes, {} edges:",
             gen.max_num_nodes(),
             initial_edges.len()
         );
-        let transformed_edges : Vec<(u32, u32, f32)> = initial_edges.into_iter().map(|(to, from, w)| (to, from, w as f32)).collect();
-        Graph::<(), f32, Directed, _>::from_edges(transformed_edges.into_iter())
+        let mut mapping = None;
+        if benchmark.permute_nodes {
+            let (permuted_edges, permutation) = permute_node_ids(&mut default_rng(benchmark.seed), initial_edges);
+            initial_edges = permuted_edges;
+            mapping = Some(permutation);
+        }
+        (Graph::<(), Weight, Ty, _>::from_edges(initial_edges.into_iter()), mapping)
     });
-    // Random generator engine.
+    // Translate the query to the permuted node IDs.
+    let translate = |node: u32| mapping.as_ref().and_then(|m| m.get(&node).cloned()).unwrap_or(node);
+    let source = translate(benchmark.search_query.source);
+    let target = translate(benchmark.search_query.target);
+    check_query(graph.node_count(), source, target);
+    // The search stops at the target. Distances are summed as u64 so long paths do not overflow.
+    let distances = timer.time_subevent("Initial", || {
+        dijkstra(&graph, NodeIndex::new(source as usize), Some(NodeIndex::new(target as usize)), |edge| *edge.weight() as u64)
+    });
+    println!(
+        "petgraph Dijkstra algorithm finished in: {:?}",
+        timer.elapsed()
+    );
+    let query = &benchmark.search_query;
+    match distances.get(&NodeIndex::new(target as usize)) {
+        Some(distance) => println!("Cost from {} to {} is {}", query.source, query.target, distance),
+        None => println!("Cost from {} to {} is inf", query.source, query.target),
+    }
+}
+
+/// Bellman-Ford on weights that can be negative, loaded from the file of a real world graph.
+fn search_signed(benchmark: &BenchmarkDescription, timer: &SubEventTimer) {
+    let path_to_edge_list = match &benchmark.graph_data {
+        GraphBenchmarkData::RealWorldGraph { path_to_edge_list, .. } => path_to_edge_list,
+        _ => panic!("--signed-weights needs a real world graph"),
+    };
+    let graph = timer.time_subevent("Loading", ||{
+        // Negative weights bypass the generator, which only holds unsigned weights.
+        let signed_edges = GraphLoader::default()
+            .with_symmetric(benchmark.symmetric)
+            .load_weighted_graph_signed(path_to_edge_list)
+            .unwrap_or_else(|error| panic!("{}", error));
+        println!("Performing SSSP on {} edges with signed weights:", signed_edges.len());
+        // Every i32 weight, and sums of many of them, are exact in f64.
+        let transformed_edges : Vec<(u32, u32, f64)> = signed_edges.into_iter().map(|(to, from, w)| (to, from, w as f64)).collect();
+        Graph::<(), f64, Directed, _>::from_edges(transformed_edges.into_iter())
+    });
+    let query = &benchmark.search_query;
+    check_query(graph.node_count(), query.source, query.target);
     let path = timer.time_subevent("Initial", || {
-        let source = NodeIndex::new(benchmark.search_query.source as usize);
-        bellman_ford(&graph, source)
+        bellman_ford(&graph, NodeIndex::new(query.source as usize))
     });
     println!(
         "petgraph Bellman-Ford algorithm finished in: {:?}",
         timer.elapsed()
     );
-    let path_bare = path.expect("No negative cost cycles");
-    let source = benchmark.search_query.source;
-    let target = benchmark.search_query.target;
-    println!("Cost from {} to {} is {}", source, target, path_bare.0[target as usize])
+    match path {
+        Ok(path_bare) => println!("Cost from {} to {} is {}", query.source, query.target, path_bare.0[query.target as usize]),
+        Err(_) => println!("Negative cycle reachable from {}, the costs are unbounded", query.source),
+    }
+}
+
+/// The graph only holds nodes up to the largest index in the edge list.
+fn check_query(num_nodes: usize, source: u32, target: u32) {
+    for (name, node) in [("Source", source), ("Target", target)].iter() {
+        if *node as usize >= num_nodes {
+            panic!("{} node {} is not in the graph, valid node indices are in the range 0..{}", name, node, num_nodes);
+        }
+    }
 }
@@ -0,0 +1,207 @@
+// Single source shortest path with turn penalties in differential dataflow.
+//
+// The search runs on the edge-expanded graph: every edge of the input graph is a
+// state, and turning from one edge onto an adjacent one costs the weight of the
+// new edge plus the turn penalty, if the turn has one.
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::timely_arguments;
+use graph_utility::GraphDataGenerator;
+use graph_utility::GraphLoader;
+use graph_utility::SubEventTimer;
+
+use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::*;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::*;
+use differential_dataflow::Collection;
+
+type Node = u32;
+type Weight = u32;
+type Edge = (Node, Node, Weight);
+/// Position of the edge in the edge list.
+type EdgeId = u32;
+/// A pair of edges (into, out of) a common node.
+type Turn = (EdgeId, EdgeId);
+
+/// Virtual state from which the search enters every edge leaving the source.
+const START_STATE: EdgeId = u32::max_value();
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let inspect: bool = benchmark.inspect_results;
+    let target = benchmark.search_query.target;
+    // Start timer.
+    let timer = SubEventTimer::new_recording().with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        let mut probe = Handle::new();
+        let (mut roots, mut graph_in, mut penalties_in) = worker.dataflow(|scope| {
+            let (root_input, roots) = scope.new_collection::<Node, isize>();
+            let (edge_input, graph) = scope.new_collection::<(EdgeId, Edge), isize>();
+            let (penalty_input, penalties) = scope.new_collection::<(Turn, Weight), isize>();
+            let mut result = sssp_turn_penalty(&graph, &penalties, &roots);
+
+            if inspect {
+                result = result.filter(move |(n, _)| *n == target);
+            } else {
+                result = result.filter(|_| false);
+            }
+
+            result
+                .map(|(_, l)| l)
+                .consolidate()
+                .inspect(|x| println!("Target node: {:?}", x))
+                .probe_with(&mut probe);
+
+            (root_input, edge_input, penalty_input)
+        });
+        for source in benchmark.search_query.sources.iter() {
+            roots.insert(*source);
+        }
+        roots.close();
+
+        // Random generator engine.
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        // Edges are numbered in the order they are inserted.
+        let mut next_edge_id: EdgeId = 0;
+        if worker_index == 0 {
+            timer.time_subevent("Loading", || {
+                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                let turn_penalties = match &benchmark.turn_penalties {
+                    Some(path) => GraphLoader::default().load_weighted_graph_or_panic(path),
+                    None => Vec::new(),
+                };
+                println!(
+                    "Performing SSSP on {} nodes, {} edges, {} turn penalties:",
+                    gen.max_num_nodes(),
+                    initial_edges.len(),
+                    turn_penalties.len()
+                );
+                // Update data only on one worker.
+                for edge in initial_edges.iter() {
+                    graph_in.update_at((next_edge_id, *edge), Default::default(), 1);
+                    next_edge_id += 1;
+                }
+                for (from_edge, to_edge, penalty) in turn_penalties.into_iter() {
+                    penalties_in.update_at(((from_edge, to_edge), penalty), Default::default(), 1);
+                }
+            });
+        }
+        penalties_in.close();
+        let mut initial_advance = || {
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+        };
+        if worker_index == 0 {
+            timer.time_subevent("Initial", initial_advance);
+        } else {
+            initial_advance();
+        }
+
+        let num_rounds = benchmark.num_rounds;
+        let initial_edges = gen.total_edges_emitted();
+        for round in 0..num_rounds {
+            if worker.index() == 0 {
+                let batch_edges = gen.gen_graph_updates(&benchmark.graph_updates);
+                // Insert the new edges with fresh identifiers.
+                for edge in batch_edges.into_iter() {
+                    graph_in.update_at((next_edge_id, edge), 1 + round, 1);
+                    next_edge_id += 1;
+                }
+            }
+            graph_in.advance_to(2 + round);
+            // Flush to input to make sure all changes are in the message queues.
+            graph_in.flush();
+            let mut update_advance = || {
+                worker.step_while(|| probe.less_than(&graph_in.time()));
+            };
+            if worker_index == 0 {
+                timer.time_subevent_with_edges(&format!("N {}", round), gen.total_edges_emitted(), update_advance);
+            } else {
+                update_advance();
+            }
+        }
+
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if num_rounds > 0 {
+                println!("Round latency: {}", timer.summary("N "));
+            }
+            println!("Throughput: {}", timer.throughput(initial_edges, gen.update_edges_emitted()));
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
+
+// returns pairs (n, d) indicating node n can be reached from a root with cost d,
+// including the penalties of all turns taken on the way.
+fn sssp_turn_penalty<G: Scope>(
+    edges: &Collection<G, (EdgeId, Edge)>,
+    penalties: &Collection<G, (Turn, Weight)>,
+    roots: &Collection<G, Node>,
+) -> Collection<G, (Node, Weight)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // Each turn costs the weight of the edge turned onto.
+    let by_target = edges.map(|(id, (_from, to, _w))| (to, id));
+    let by_source = edges.map(|(id, (from, _to, w))| (from, (id, w)));
+    let turns = by_target.join_map(&by_source, |_node, &into, &(out, w)| ((into, out), w));
+    let penalized = turns.join_map(penalties, |&(into, out), &w, &penalty| (into, out, w + penalty));
+    let unpenalized = turns
+        .antijoin(&penalties.map(|(turn, _)| turn).distinct())
+        .map(|((into, out), w)| (into, out, w));
+    // Leaving a root costs the weight of the first edge.
+    let starts = by_source
+        .semijoin(roots)
+        .map(|(_root, (out, w))| (START_STATE, out, w));
+    let expanded = penalized.concat(&unpenalized).concat(&starts);
+
+    let states = roots.map(|_| START_STATE).distinct();
+    // Cost of reaching each node is the cheapest state arriving at it.
+    sssp(&expanded, &states)
+        .join_map(edges, |_id, &cost, &(_from, to, _w)| (to, cost))
+        .concat(&roots.map(|root| (root, 0)))
+        .reduce(|_, input, output| output.push((*input[0].0, 1)))
+}
+
+fn sssp<G: Scope>(
+    edges: &Collection<G, Edge>,
+    roots: &Collection<G, Node>,
+) -> Collection<G, (Node, Weight)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // initialize roots as reaching themselves at distance 0
+    let nodes = roots.map(|x| (x, 0));
+    // Repeatedly update minimal distances each node can be reached from each root
+    nodes.iterate(|inner| {
+        let edges = edges
+            .enter(&inner.scope())
+            .map(|(from, to, w)| (from, (to, w)));
+        let nodes = nodes.enter(&inner.scope());
+        inner
+            .join_map(&edges, |_from, &cost, &(to, w)| (to, cost + w))
+            .concat(&nodes)
+            // Note: reduce receives its input as an ordered collection.
+            .reduce(|_, input, output| output.push((*input[0].0, 1)))
+    })
+}
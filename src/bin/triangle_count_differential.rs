@@ -0,0 +1,157 @@
+// Triangle counting in differential dataflow
+//
+// Counts the triangles of the undirected graph underlying the generated or loaded graph,
+// ignoring edge weights, self loops and duplicate edges. Each update round inserts new
+// edges, and the change in the number of triangles is reported after every round.
+
+extern crate differential_dataflow;
+extern crate graph_utility;
+extern crate timely;
+
+use graph_utility::parse_graph_benchmark_arguments;
+use graph_utility::timely_arguments;
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+
+use timely::dataflow::operators::probe::Handle;
+use timely::dataflow::operators::{Exchange, Inspect, Probe};
+use timely::dataflow::*;
+
+use differential_dataflow::input::Input;
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::*;
+use differential_dataflow::Collection;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+type Node = u32;
+type Edge = (Node, Node);
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+    let inspect: bool = benchmark.inspect_results;
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Computation context definition.
+    timely::execute_from_args(timely_arguments(std::env::args()), move |worker| {
+        let worker_index = worker.index();
+        let mut probe = Handle::new();
+        // Number of triangles gathered on worker 0.
+        let num_triangles = Rc::new(Cell::new(0isize));
+        let mut graph_in = worker.dataflow(|scope| {
+            let (edge_input, graph) = scope.new_collection();
+            let num_triangles = num_triangles.clone();
+            triangles(&graph)
+                .map(|_| ())
+                .count()
+                .inner
+                .exchange(|_| 0)
+                // The count is a single record, so the sum of the changes is its current value.
+                .inspect(move |(((), count), _time, diff)| {
+                    num_triangles.set(num_triangles.get() + *count * *diff);
+                })
+                .probe_with(&mut probe);
+
+            edge_input
+        });
+
+        // Random generator engine.
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        if worker_index == 0 {
+            timer.time_subevent("Loading", || {
+                let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+                println!(
+                    "Performing triangle counting on {} nodes, {} edges:",
+                    gen.max_num_nodes(),
+                    initial_edges.len()
+                );
+                // Update data only on one worker.
+                for (from, to, _) in initial_edges.into_iter() {
+                    graph_in.update_at((from, to), Default::default(), 1);
+                }
+            });
+        }
+        let mut initial_advance = || {
+            graph_in.advance_to(1);
+            graph_in.flush();
+            worker.step_while(|| probe.less_than(graph_in.time()));
+        };
+        if worker_index == 0 {
+            timer.time_subevent("Initial", initial_advance);
+        } else {
+            initial_advance();
+        }
+
+        // Warmup rounds run first, through the same update path, but are neither timed nor reported.
+        let warmup_rounds = benchmark.warmup_rounds;
+        let num_rounds = benchmark.num_rounds;
+        let rounds_per_batch = benchmark.rounds_per_batch;
+        for round in 0..warmup_rounds + num_rounds {
+            let before = num_triangles.get();
+            if worker.index() == 0 {
+                // Insert elements for update
+                for (from, to, _) in gen.gen_graph_updates(&benchmark.graph_updates).into_iter() {
+                    graph_in.update_at((from, to), 1 + round, 1);
+                }
+            }
+            if !benchmark.ends_batch(round) {
+                // The changes stay at their round's time until the batch is complete.
+                continue;
+            }
+            graph_in.advance_to(2 + round);
+            // Flush to input to make sure all changes are in the message queues.
+            graph_in.flush();
+            let mut update_advance = || {
+                worker.step_while(|| probe.less_than(&graph_in.time()));
+            };
+            if round < warmup_rounds {
+                update_advance();
+            } else if worker_index == 0 {
+                let name = format!("N {}", (round - warmup_rounds) / rounds_per_batch);
+                timer.time_subevent(&name, update_advance);
+                println!("Triangle delta: {:10}{:+}", name, num_triangles.get() - before);
+            } else {
+                update_advance();
+            }
+        }
+
+        println!(
+            "Worker {} finished in: {:?}",
+            worker.index(),
+            timer.elapsed()
+        );
+        if worker_index == 0 {
+            if inspect {
+                println!("Triangles: {}", num_triangles.get());
+            }
+            if let Some(path) = &benchmark.trace_output {
+                timer.write_chrome_trace(path).expect("Could not write trace file");
+            }
+        }
+    })
+    .unwrap();
+}
+
+// returns triples (a, b, c) with a < b < c for every triangle of the undirected graph.
+fn triangles<G: Scope>(
+    edges: &Collection<G, Edge>,
+) -> Collection<G, (Node, Node, Node)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // Each undirected edge once, from its smaller to its larger node.
+    let edges = edges
+        .filter(|(from, to)| from != to)
+        .map(|(from, to)| (std::cmp::min(from, to), std::cmp::max(from, to)))
+        .distinct();
+    // Pairs of edges from the smallest node of a triangle, closed by the edge between the other two.
+    edges
+        .join_map(&edges, |&a, &b, &c| ((b, c), a))
+        .filter(|((b, c), _)| b < c)
+        .semijoin(&edges)
+        .map(|((b, c), a)| (a, b, c))
+}
@@ -0,0 +1,65 @@
+/// Baseline all-pairs shortest paths on a petgraph graph, with a Dijkstra
+/// search from every node, reporting the Wiener index of the graph: the sum
+/// of the shortest path distances between all pairs of nodes.
+
+extern crate graph_utility;
+extern crate petgraph;
+
+use graph_utility::GraphDataGenerator;
+use graph_utility::SubEventTimer;
+use graph_utility::parse_graph_benchmark_arguments;
+
+use petgraph::algo::dijkstra;
+use petgraph::prelude::*;
+use petgraph::Graph;
+
+/// All pairs searches take quadratic time and more, so larger graphs are refused.
+const MAX_NODES: usize = 10_000;
+
+fn main() {
+    // Parse arguments.
+    let benchmark = parse_graph_benchmark_arguments(std::env::args());
+
+    // Start timer.
+    let timer = SubEventTimer::new_timer().with_span_trace(benchmark.trace_output.is_some())
+        .with_cpu_time(benchmark.cpu_time).with_memory(benchmark.memory);
+
+    // Measure data loading.
+    let graph = timer.time_subevent("Loading", || {
+        let mut gen = GraphDataGenerator::new_from_seed(benchmark.seed).with_symmetric(benchmark.symmetric);
+        let initial_edges = gen.gen_initial_graph(&benchmark.graph_data);
+        println!(
+            "Performing APSP on {} nodes, {} edges:",
+            gen.max_num_nodes(),
+            initial_edges.len()
+        );
+        Graph::<(), u32, Directed, u32>::from_edges(initial_edges.into_iter())
+    });
+    if graph.node_count() > MAX_NODES {
+        panic!("The Wiener index is only computed for graphs of at most {} nodes, this one has {}", MAX_NODES, graph.node_count());
+    }
+
+    let (total, unreachable) = timer.time_subevent("Initial", || {
+        let mut total = 0u64;
+        let mut reached = 0usize;
+        for source in graph.node_indices() {
+            let distances = dijkstra(&graph, source, None, |edge| *edge.weight());
+            total += distances.values().map(|distance| *distance as u64).sum::<u64>();
+            reached += distances.len();
+        }
+        (total, graph.node_count() * graph.node_count() - reached)
+    });
+    println!(
+        "petgraph Dijkstra APSP finished in: {:?}",
+        timer.elapsed()
+    );
+    // Every unordered pair is counted in both directions, as undirected
+    // graphs list each edge in both directions.
+    println!("Wiener index: {}", total / 2);
+    if unreachable > 0 {
+        println!("Skipped {} ordered pairs of nodes without a path, the graph is not connected", unreachable);
+    }
+    if let Some(path) = &benchmark.trace_output {
+        timer.write_chrome_trace(path).expect("Could not write trace file");
+    }
+}
@@ -7,6 +7,33 @@
 /// reproducible on different machines.
 extern crate rand;
 extern crate rand_chacha;
+/// Process resource usage for CPU time measurements.
+#[cfg(unix)]
+extern crate libc;
+/// Parquet files, only with the `parquet` feature.
+#[cfg(feature = "parquet")]
+extern crate parquet;
+/// Timely and differential dataflow, and the serialization their data types need,
+/// for the shared dataflows. Differential dataflow only with the `differential` feature.
+extern crate timely;
+#[cfg(feature = "differential")]
+extern crate differential_dataflow;
+#[macro_use]
+extern crate abomonation_derive;
+extern crate abomonation;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+/// Single source shortest path dataflows, only with the `differential` feature.
+#[cfg(feature = "differential")]
+pub mod sssp;
+#[cfg(feature = "differential")]
+pub use sssp::{reverse, run_sssp, undirected, BenchmarkReport};
+/// Coordination between the timely workers, such as loading the graph in parallel, only
+/// with the `differential` feature.
+#[cfg(feature = "differential")]
+pub mod workers;
 
 /// Exported types representing graphs.
 /// Note, these are just type aliases to tuples of elements. The reason we are doing it like so
@@ -16,314 +43,3820 @@ extern crate rand_chacha;
 
 pub type Node = u32;
 pub type Weight = u32;
+/// Weights that can be negative, only loaded by `GraphLoader::load_weighted_graph_signed`
+/// for the baselines that support them. The other computations assume non-negative weights.
+pub type SignedWeight = i32;
+
+pub type Capacity = u32;
 
 pub type UnweightedEdge = (Node, Node);
 pub type WeightedEdge = (Node, Node, Weight);
+pub type SignedEdge = (Node, Node, SignedWeight);
+pub type CapacitatedEdge = (Node, Node, Weight, Capacity);
+
+pub type Time = u32;
+/// A connection leaving the source at the departure time and reaching the target at the arrival time.
+pub type TemporalEdge = (Node, Node, Time, Time);
 
 /// Convenience methods for loading graphs.
-/// Graph files are simply whitespace separated lists of numbers.
+/// Graph files are simply whitespace separated lists of numbers, and lines starting
+/// with "#" or another configured comment prefix are skipped.
+
+/// Errors from loading a graph file. Line numbers are 1-based lines of the file.
+#[derive(Debug)]
+pub enum GraphLoadError {
+    Io(std::io::Error),
+    MissingField { line: usize },
+    ParseInt { line: usize, source: std::num::ParseIntError },
+    ParseFloat { line: usize, source: std::num::ParseFloatError },
+    /// A negative number where only non-negative ones are allowed.
+    Negative { line: usize },
+    /// An edge ID listed a second time, in the weights or the topology file of
+    /// `load_weighted_graph_by_edge_id`.
+    DuplicateEdgeId { line: usize, id: u32 },
+    /// An edge of the topology file whose ID has no weight.
+    MissingEdgeId { line: usize, id: u32 },
+    /// A connection of a temporal graph arriving before it departs.
+    ArrivalBeforeDeparture { line: usize },
+    /// A row of an adjacency matrix with another number of entries than the first row.
+    RowLength { line: usize, entries: usize, expected: usize },
+    /// An adjacency matrix with another number of rows than of columns.
+    NotSquare { rows: usize, columns: usize },
+    /// A header line not of the form `expected`, or missing at the end of the file.
+    InvalidHeader { line: usize, expected: &'static str },
+    /// A variant of a file format that the loader does not support.
+    Unsupported { line: usize, what: String },
+    /// A 1-indexed node outside `1..=num_nodes`.
+    NodeOutOfRange { line: usize, node: Node, num_nodes: Node },
+    /// A file listing another number of nodes or entries than its header.
+    WrongCount { what: &'static str, found: usize, expected: usize },
+}
+
+impl std::fmt::Display for GraphLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GraphLoadError::Io(error) => write!(f, "Could not read graph file: {}", error),
+            GraphLoadError::MissingField { line } => write!(f, "Missing field on line {}", line),
+            GraphLoadError::ParseInt { line, source } => write!(f, "Invalid number on line {}: {}", line, source),
+            GraphLoadError::ParseFloat { line, source } => write!(f, "Invalid number on line {}: {}", line, source),
+            GraphLoadError::Negative { line } => {
+                write!(f, "Negative number on line {}, only load_weighted_graph_signed accepts negative weights", line)
+            }
+            GraphLoadError::DuplicateEdgeId { line, id } => write!(f, "Duplicate edge id {} on line {}", id, line),
+            GraphLoadError::MissingEdgeId { line, id } => write!(f, "Edge id {} on line {} has no weight", id, line),
+            GraphLoadError::ArrivalBeforeDeparture { line } => write!(f, "Connection on line {} arrives before it departs", line),
+            GraphLoadError::RowLength { line, entries, expected } => {
+                write!(f, "Adjacency matrix row on line {} has {} entries, expected {}", line, entries, expected)
+            }
+            GraphLoadError::NotSquare { rows, columns } => {
+                write!(f, "Adjacency matrix must be square, it has {} rows of {} entries", rows, columns)
+            }
+            GraphLoadError::InvalidHeader { line, expected } => write!(f, "Line {} must be \"{}\"", line, expected),
+            GraphLoadError::Unsupported { line, what } => write!(f, "Unsupported {} on line {}", what, line),
+            GraphLoadError::NodeOutOfRange { line, node, num_nodes } => {
+                write!(f, "Node {} on line {} is not in 1..={}", node, line, num_nodes)
+            }
+            GraphLoadError::WrongCount { what, found, expected } => write!(f, "File lists {} of its {} {}", found, expected, what),
+        }
+    }
+}
+
+impl std::error::Error for GraphLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GraphLoadError::Io(error) => Some(error),
+            GraphLoadError::MissingField { .. }
+            | GraphLoadError::Negative { .. }
+            | GraphLoadError::DuplicateEdgeId { .. }
+            | GraphLoadError::MissingEdgeId { .. }
+            | GraphLoadError::ArrivalBeforeDeparture { .. }
+            | GraphLoadError::RowLength { .. }
+            | GraphLoadError::NotSquare { .. }
+            | GraphLoadError::InvalidHeader { .. }
+            | GraphLoadError::Unsupported { .. }
+            | GraphLoadError::NodeOutOfRange { .. }
+            | GraphLoadError::WrongCount { .. } => None,
+            GraphLoadError::ParseInt { source, .. } => Some(source),
+            GraphLoadError::ParseFloat { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<std::io::Error> for GraphLoadError {
+    fn from(error: std::io::Error) -> GraphLoadError {
+        GraphLoadError::Io(error)
+    }
+}
+
+/// Names of the source, target and weight columns of a Parquet edge list.
+#[cfg(feature = "parquet")]
+#[derive(Clone, Debug)]
+pub struct ParquetColumns {
+    pub source: String,
+    pub target: String,
+    pub weight: String,
+}
+
+#[cfg(feature = "parquet")]
+impl Default for ParquetColumns {
+    fn default() -> ParquetColumns {
+        ParquetColumns { source: "src".to_string(), target: "dst".to_string(), weight: "weight".to_string() }
+    }
+}
+
+/// Parse a required field of the given 1-based line.
+fn parse_field(field: Option<&str>, line: usize) -> Result<u32, GraphLoadError> {
+    let field = field.ok_or(GraphLoadError::MissingField { line: line })?;
+    if field.starts_with('-') && field.len() > 1 {
+        return Err(GraphLoadError::Negative { line: line });
+    }
+    field.parse().map_err(|error| GraphLoadError::ParseInt { line: line, source: error })
+}
+
+/// Parse a field that can be negative, see `parse_field`.
+fn parse_signed_field(field: Option<&str>, line: usize) -> Result<i32, GraphLoadError> {
+    let field = field.ok_or(GraphLoadError::MissingField { line: line })?;
+    field.parse().map_err(|error| GraphLoadError::ParseInt { line: line, source: error })
+}
+
+/// Mapping between the original node IDs of a file and a dense range `0..num_nodes`.
+#[derive(Clone, Debug, Default)]
+pub struct NodeRemap {
+    pub original_to_dense: std::collections::HashMap<Node, Node>,
+    /// Original ID of every dense ID, indexed by the dense ID.
+    pub dense_to_original: Vec<Node>,
+}
+
+impl NodeRemap {
+    /// Dense ID of an original ID, assigning the next free one if it was not seen yet.
+    fn dense(&mut self, original: Node) -> Node {
+        let next = self.dense_to_original.len() as Node;
+        let dense_to_original = &mut self.dense_to_original;
+        *self.original_to_dense.entry(original).or_insert_with(|| {
+            dense_to_original.push(original);
+            next
+        })
+    }
+}
+
+/// File name under which `GraphLoader` reads a graph from standard input instead.
+pub const STDIN_FILENAME: &str = "-";
 
 /// Graph loader holding the number of indexes and peers. Useful for multi-worker loading.
+/// Files can be read from standard input by naming them `-`, but only with a single
+/// peer, since standard input cannot be read again by the other peers.
 pub struct GraphLoader {
     index: usize,
     peers: usize,
+    dedup: bool,
+    drop_self_loops: bool,
+    comment_prefixes: Vec<String>,
+    header: bool,
+    symmetric: bool,
+    progress: bool,
+    limit: Option<usize>,
 }
 
-impl GraphLoader {
+/// Number of lines between the progress reports of `GraphLoader::with_progress`.
+const PROGRESS_LINES: usize = 10_000_000;
+
+impl GraphLoader {
+
+    pub fn default() -> GraphLoader {
+        GraphLoader::new(0, 1)
+    }
+
+    pub fn new(index: usize, peers: usize) -> GraphLoader {
+        GraphLoader {index: index, peers: peers, dedup: false, drop_self_loops: false, comment_prefixes: vec!["#".to_string()], header: false, symmetric: false, progress: false, limit: None}
+    }
+
+    /// Skip lines starting with `prefix` instead of "#".
+    pub fn with_comment_prefix(self, prefix: &str) -> GraphLoader {
+        self.with_comment_prefixes(&[prefix])
+    }
+
+    /// Skip lines starting with any of `prefixes` instead of "#", for example
+    /// `&["c", "p"]` for the comment and problem lines of DIMACS files.
+    pub fn with_comment_prefixes(mut self, prefixes: &[&str]) -> GraphLoader {
+        self.comment_prefixes = prefixes.iter().map(|prefix| prefix.to_string()).collect();
+        self
+    }
+
+    /// Skip the first line of CSV files, which names the columns.
+    pub fn with_header(mut self, enabled: bool) -> GraphLoader {
+        self.header = enabled;
+        self
+    }
+
+    /// Print the number of lines read so far to standard error every ten million lines,
+    /// to follow the loading of large files. Each peer reports the lines it went through.
+    pub fn with_progress(mut self, enabled: bool) -> GraphLoader {
+        self.progress = enabled;
+        self
+    }
+
+    /// Stop reading once `limit` edges are loaded, for quick runs on the start of a large file.
+    /// Each peer stops after its share of the limit, so the peers load `limit` edges together
+    /// from a long enough file. Edges count as they are read, before `with_symmetric`,
+    /// `with_dedup` and `with_drop_self_loops` apply. Only edge list loaders are limited, not
+    /// `load_adjacency_matrix`, `load_metis` and `load_matrix_market`, which check the whole file.
+    pub fn with_limit(mut self, limit: Option<usize>) -> GraphLoader {
+        self.limit = limit;
+        self
+    }
+
+    /// This peer's share of the limit, with the remainder going to the first peers.
+    fn peer_limit(&self) -> Option<usize> {
+        self.limit.map(|limit| limit / self.peers + if self.index < limit % self.peers { 1 } else { 0 })
+    }
+
+    fn limit_reached(&self, loaded: usize) -> bool {
+        self.peer_limit().map_or(false, |limit| loaded >= limit)
+    }
+
+    fn report_progress(&self, filename: &str, lines: usize) {
+        if self.progress && lines % PROGRESS_LINES == 0 {
+            eprintln!("Peer {} of {}: read {} lines of {}", self.index, self.peers, lines, filename);
+        }
+    }
+
+    /// Open a file for buffered reading, or standard input for `STDIN_FILENAME`.
+    fn open_input(&self, filename: &str) -> std::io::Result<Box<dyn std::io::BufRead>> {
+        use std::io::{BufReader, Error, ErrorKind};
+
+        if filename != STDIN_FILENAME {
+            return Ok(Box::new(BufReader::new(std::fs::File::open(filename)?)));
+        }
+        if self.peers > 1 {
+            return Err(Error::new(ErrorKind::InvalidInput, "standard input can only be read by a single peer"));
+        }
+        Ok(Box::new(BufReader::new(std::io::stdin())))
+    }
+
+    fn is_comment(&self, line: &str) -> bool {
+        self.comment_prefixes.iter().any(|prefix| line.starts_with(prefix.as_str()))
+    }
+
+    /// Keep only one of the edges with the same source and target, the one with the lowest
+    /// weight. The edges are then returned sorted by source and target. Duplicates are only
+    /// found among the edges loaded by the same peer.
+    pub fn with_dedup(mut self, enabled: bool) -> GraphLoader {
+        self.dedup = enabled;
+        self
+    }
+
+    /// Remove the edges from a node to itself.
+    pub fn with_drop_self_loops(mut self, enabled: bool) -> GraphLoader {
+        self.drop_self_loops = enabled;
+        self
+    }
+
+    /// Also load the reverse `(v, u, w)` of every edge `(u, v, w)`, for files listing each
+    /// undirected edge once. Self loops are not doubled, and `with_dedup` runs afterwards,
+    /// so it only drops reverse edges that were also in the file.
+    pub fn with_symmetric(mut self, enabled: bool) -> GraphLoader {
+        self.symmetric = enabled;
+        self
+    }
+
+    /// Apply `with_symmetric`, `with_dedup` and `with_drop_self_loops` to loaded edges of any kind,
+    /// given the source and target of an edge, its reverse, and the key under which `with_dedup`
+    /// keeps only the smallest edge.
+    fn clean_edges<E: Ord, K: PartialEq>(
+        &self,
+        mut edges: Vec<E>,
+        endpoints: impl Fn(&E) -> (Node, Node),
+        reverse: impl Fn(&E) -> E,
+        key: impl Fn(&E) -> K,
+    ) -> Vec<E> {
+        let is_self_loop = |edge: &E| {
+            let (from, to) = endpoints(edge);
+            from == to
+        };
+        if self.symmetric {
+            let reversed: Vec<E> = edges.iter().filter(|edge| !is_self_loop(edge)).map(&reverse).collect();
+            edges.extend(reversed);
+        }
+        if self.drop_self_loops {
+            edges.retain(|edge| !is_self_loop(edge));
+        }
+        if self.dedup {
+            edges.sort();
+            edges.dedup_by(|edge, kept| key(edge) == key(kept));
+        }
+        edges
+    }
+
+    /// Apply `with_symmetric`, `with_dedup` and `with_drop_self_loops` to loaded weighted edges.
+    fn clean_weighted(&self, edges: Vec<WeightedEdge>) -> Vec<WeightedEdge> {
+        self.clean_edges(edges, |&(from, to, _)| (from, to), |&(from, to, weight)| (to, from, weight), |&(from, to, _)| (from, to))
+    }
+
+    /// Apply `with_symmetric`, `with_dedup` and `with_drop_self_loops` to loaded unweighted edges.
+    fn clean_unweighted(&self, edges: Vec<UnweightedEdge>) -> Vec<UnweightedEdge> {
+        self.clean_edges(edges, |&edge| edge, |&(from, to)| (to, from), |&edge| edge)
+    }
+
+    /// Load from a file containing triplets of numbers: "source target weight"
+    pub fn load_weighted_graph(&self, filename: &str) -> Result<Vec<WeightedEdge>, GraphLoadError> {
+        Ok(self.load_weighted_graph_with_nodes(filename)?.0)
+    }
+
+    /// Like `load_weighted_graph`, but panics with the error.
+    pub fn load_weighted_graph_or_panic(&self, filename: &str) -> Vec<WeightedEdge> {
+        self.load_weighted_graph(filename).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Load from a file containing triplets of numbers: "source target weight", reading only
+    /// this peer's share of the file. The file is split into `peers` byte ranges of equal size,
+    /// and each line is loaded by the peer whose range contains its first byte, so every edge
+    /// is loaded exactly once across the peers. Comments, blank lines and isolated node
+    /// declarations are skipped as in `load_weighted_graph`, but line numbers in errors count
+    /// from the start of this peer's range, since the lines before it are never read.
+    pub fn load_weighted_graph_partitioned(&self, filename: &str) -> Result<Vec<WeightedEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::{BufRead, BufReader, Seek, SeekFrom};
+        use std::fs::File;
+
+        if filename == STDIN_FILENAME {
+            let error = std::io::Error::new(std::io::ErrorKind::InvalidInput, "standard input cannot be split between peers");
+            return Err(GraphLoadError::from(error));
+        }
+        let file = File::open(filename)?;
+        let size = file.metadata()?.len();
+        let begin = size * self.index as u64 / self.peers as u64;
+        let end = size * (self.index as u64 + 1) / self.peers as u64;
+        let mut reader = BufReader::new(file);
+        // Skip the line started by the previous peer, unless the range begins a new line.
+        let mut position = begin;
+        if begin > 0 {
+            reader.seek(SeekFrom::Start(begin - 1))?;
+            position = begin - 1 + reader.read_until(b'\n', &mut Vec::new())? as u64;
+        }
+
+        let mut data = Vec::new();
+        let mut line = String::new();
+        let mut count = 0;
+        while position < end && !self.limit_reached(data.len()) {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            position += read as u64;
+            count += 1;
+            self.report_progress(filename, count);
+            if self.is_comment(&line) || line.trim().is_empty() {
+                continue;
+            }
+            let mut text = line.split_whitespace();
+            let from = parse_field(text.next(), count)?;
+            let to = match text.next() {
+                Some(to) => parse_field(Some(to), count)?,
+                None => continue,
+            };
+            let weight = parse_field(text.next(), count)?;
+            data.push((from, to, weight));
+        }
+        Ok(self.clean_weighted(data))
+    }
+
+    /// Like `load_weighted_graph_with_nodes`, but renumbers the nodes to the dense range
+    /// `0..num_nodes` in the order they first appear in the file, isolated nodes included.
+    /// Returns the renumbered edges and the mapping, for translating the search query.
+    /// Every peer numbers only the nodes of its own lines, so use it with a single peer.
+    pub fn load_weighted_graph_remapped(&self, filename: &str) -> Result<(Vec<WeightedEdge>, NodeRemap), GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut data = Vec::new();
+        let mut remap = NodeRemap::default();
+        let file = self.open_input(filename)?;
+
+        for (count, read_line) in file.lines().enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            self.report_progress(filename, count + 1);
+            if count % self.peers == self.index {
+                let line = read_line?;
+                if self.is_comment(&line) || line.trim().is_empty() {
+                    continue;
+                }
+                let mut text = line.split_whitespace();
+                let from = remap.dense(parse_field(text.next(), count + 1)?);
+                let to = match text.next() {
+                    Some(to) => remap.dense(parse_field(Some(to), count + 1)?),
+                    None => continue,
+                };
+                let weight = parse_field(text.next(), count + 1)?;
+                data.push((from, to, weight));
+            }
+        }
+        Ok((self.clean_weighted(data), remap))
+    }
+
+    /// Lazily read edges from a file containing triplets of numbers: "source target weight",
+    /// without holding the graph in memory. Comments, blank lines, isolated node declarations
+    /// and the striding across peers are handled as in `load_weighted_graph`. Self loops are
+    /// dropped with `with_drop_self_loops`, and reverse edges follow their edge with
+    /// `with_symmetric`, but `with_dedup` does not apply to streamed edges.
+    pub fn stream_weighted_graph(&self, filename: &str) -> impl Iterator<Item = Result<WeightedEdge, GraphLoadError>> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let (index, peers, drop_self_loops, symmetric) = (self.index, self.peers, self.drop_self_loops, self.symmetric);
+        let comment_prefixes = self.comment_prefixes.clone();
+        let progress = self.progress;
+        let limit = self.peer_limit().unwrap_or(usize::max_value());
+        let name = filename.to_string();
+        // A file that cannot be opened yields its error as the only item.
+        let lines: Box<dyn Iterator<Item = std::io::Result<String>>> = match self.open_input(filename) {
+            Ok(file) => Box::new(file.lines()),
+            Err(error) => Box::new(std::iter::once(Err(error))),
+        };
+        lines
+            .enumerate()
+            .filter_map(move |(count, read_line)| {
+                if progress && (count + 1) % PROGRESS_LINES == 0 {
+                    eprintln!("Peer {} of {}: read {} lines of {}", index, peers, count + 1, name);
+                }
+                // Lines of other peers are skipped before looking at them, as in the eager loaders.
+                if count % peers != index {
+                    return None;
+                }
+                let line = match read_line {
+                    Ok(line) => line,
+                    Err(error) => return Some(Err(GraphLoadError::from(error))),
+                };
+                let is_comment = comment_prefixes.iter().any(|prefix| line.starts_with(prefix.as_str()));
+                if is_comment || line.trim().is_empty() {
+                    return None;
+                }
+                let mut text = line.split_whitespace();
+                let edge = (|| {
+                    let from = parse_field(text.next(), count + 1)?;
+                    let to = match text.next() {
+                        Some(to) => parse_field(Some(to), count + 1)?,
+                        None => return Ok(None),
+                    };
+                    let weight = parse_field(text.next(), count + 1)?;
+                    if drop_self_loops && from == to {
+                        return Ok(None);
+                    }
+                    Ok(Some((from, to, weight)))
+                })();
+                edge.transpose()
+            })
+            // Only edges count towards the limit, errors pass through until it is reached.
+            .scan(0, move |loaded, edge| {
+                if *loaded >= limit {
+                    return None;
+                }
+                if edge.is_ok() {
+                    *loaded += 1;
+                }
+                Some(edge)
+            })
+            .flat_map(move |edge| match edge {
+                Ok((from, to, weight)) if symmetric && from != to => vec![Ok((from, to, weight)), Ok((to, from, weight))],
+                edge => vec![edge],
+            })
+    }
+
+    /// Load from a file containing triplets of numbers: "source target weight",
+    /// and lines with a single number declaring an isolated node, which are returned separately.
+    /// Blank lines are skipped.
+    pub fn load_weighted_graph_with_nodes(&self, filename: &str) -> Result<(Vec<WeightedEdge>, Vec<Node>), GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut data = Vec::new();
+        let mut isolated = Vec::new();
+        let file = self.open_input(filename)?;
+        let lines = file.lines();
+        
+        for (count, read_line) in lines.enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            self.report_progress(filename, count + 1);
+            if count % self.peers == self.index {
+                let line = read_line?;
+                if self.is_comment(&line) || line.trim().is_empty() {
+                    continue;
+                }
+                let mut text = line.split_whitespace();
+                let from = parse_field(text.next(), count + 1)?;
+                let to = match text.next() {
+                    Some(to) => parse_field(Some(to), count + 1)?,
+                    None => {
+                        isolated.push(from);
+                        continue;
+                    }
+                };
+                let weight = parse_field(text.next(), count + 1)?;
+                data.push((from, to, weight));
+            }
+        }
+        Ok((self.clean_weighted(data), isolated))
+    }
+
+    /// Like `load_weighted_graph`, but the weights can be negative. Isolated node declarations
+    /// are skipped, and `with_dedup` keeps the lowest weight as there.
+    pub fn load_weighted_graph_signed(&self, filename: &str) -> Result<Vec<SignedEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut data = Vec::new();
+        let file = self.open_input(filename)?;
+
+        for (count, read_line) in file.lines().enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            self.report_progress(filename, count + 1);
+            if count % self.peers == self.index {
+                let line = read_line?;
+                if self.is_comment(&line) || line.trim().is_empty() {
+                    continue;
+                }
+                let mut text = line.split_whitespace();
+                let from = parse_field(text.next(), count + 1)?;
+                let to = match text.next() {
+                    Some(to) => parse_field(Some(to), count + 1)?,
+                    None => continue,
+                };
+                let weight = parse_signed_field(text.next(), count + 1)?;
+                data.push((from, to, weight));
+            }
+        }
+        Ok(self.clean_edges(data, |&(from, to, _)| (from, to), |&(from, to, weight)| (to, from, weight), |&(from, to, _)| (from, to)))
+    }
+
+    /// Load from a CSV file with rows "source,target,weight". Spaces around the fields are
+    /// ignored, and comments and blank lines are skipped as in `load_weighted_graph`.
+    /// With `with_header(true)` the first line is skipped, while still counting towards
+    /// the line numbers in errors.
+    pub fn load_weighted_graph_csv(&self, filename: &str) -> Result<Vec<WeightedEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut data = Vec::new();
+        let file = self.open_input(filename)?;
+        let lines = file.lines();
+
+        for (count, read_line) in lines.enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            self.report_progress(filename, count + 1);
+            if count % self.peers == self.index {
+                let line = read_line?;
+                if (self.header && count == 0) || self.is_comment(&line) || line.trim().is_empty() {
+                    continue;
+                }
+                let mut text = line.split(',').map(|field| field.trim());
+                let from = parse_field(text.next(), count + 1)?;
+                let to = parse_field(text.next(), count + 1)?;
+                let weight = parse_field(text.next(), count + 1)?;
+                data.push((from, to, weight));
+            }
+        }
+        Ok(self.clean_weighted(data))
+    }
+
+    /// Load from a file containing quadruplets of numbers: "source target weight capacity".
+    /// Comments and blank lines are skipped as in `load_weighted_graph`. Reverse edges have the
+    /// same weight and capacity, and `with_dedup` keeps the lowest weight, then capacity.
+    pub fn load_capacitated_graph(&self, filename: &str) -> Result<Vec<CapacitatedEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut data = Vec::new();
+        let file = self.open_input(filename)?;
+        let lines = file.lines();
+
+        for (count, read_line) in lines.enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            self.report_progress(filename, count + 1);
+            if count % self.peers == self.index {
+                let line = read_line?;
+                if self.is_comment(&line) || line.trim().is_empty() {
+                    continue;
+                }
+                let mut text = line.split_whitespace();
+                let from = parse_field(text.next(), count + 1)?;
+                let to = parse_field(text.next(), count + 1)?;
+                let weight = parse_field(text.next(), count + 1)?;
+                let capacity = parse_field(text.next(), count + 1)?;
+                data.push((from, to, weight, capacity));
+            }
+        }
+        Ok(self.clean_edges(
+            data,
+            |&(from, to, _, _)| (from, to),
+            |&(from, to, weight, capacity)| (to, from, weight, capacity),
+            |&(from, to, _, _)| (from, to),
+        ))
+    }
+
+    /// Load node attributes from a file containing pairs: "node attribute", where the
+    /// attribute is any whitespace free text. Every peer loads all attributes, since
+    /// filtering its edges may need the attributes of any node.
+    pub fn load_node_attributes(&self, filename: &str) -> Result<std::collections::HashMap<Node, String>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut attributes = std::collections::HashMap::new();
+        let file = self.open_input(filename)?;
+        for (count, read_line) in file.lines().enumerate() {
+            let line = read_line?;
+            if self.is_comment(&line) || line.trim().is_empty() {
+                continue;
+            }
+            let mut text = line.split_whitespace();
+            let node = parse_field(text.next(), count + 1)?;
+            let attribute = text.next().ok_or(GraphLoadError::MissingField { line: count + 1 })?;
+            attributes.insert(node, attribute.to_string());
+        }
+        Ok(attributes)
+    }
+
+    /// Load from a Parquet file with integer columns for the source, target and weight of each
+    /// edge, as written by pandas or Arrow. Rows are strided across peers like lines of text files.
+    #[cfg(feature = "parquet")]
+    pub fn load_parquet(&self, filename: &str, columns: &ParquetColumns) -> Result<Vec<WeightedEdge>, parquet::errors::ParquetError> {
+        use parquet::errors::ParquetError;
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::Field;
+        use std::convert::TryFrom;
+        use std::fs::File;
+
+        fn field_value(field: &Field) -> Option<u32> {
+            match *field {
+                Field::Int(value) => u32::try_from(value).ok(),
+                Field::Long(value) => u32::try_from(value).ok(),
+                Field::UInt(value) => Some(value),
+                Field::ULong(value) => u32::try_from(value).ok(),
+                _ => None,
+            }
+        }
+
+        let reader = SerializedFileReader::new(File::open(filename)?)?;
+        let mut data = Vec::new();
+        for (count, row) in reader.get_row_iter(None)?.enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            if count % self.peers != self.index {
+                continue;
+            }
+            let (mut from, mut to, mut weight) = (None, None, None);
+            for (name, field) in row.get_column_iter() {
+                if *name == columns.source {
+                    from = Some(field);
+                } else if *name == columns.target {
+                    to = Some(field);
+                } else if *name == columns.weight {
+                    weight = Some(field);
+                }
+            }
+            let value = |field: Option<&Field>, column: &str| {
+                let field = field.ok_or_else(|| ParquetError::General(format!("Missing column {}", column)))?;
+                field_value(field).ok_or_else(|| ParquetError::General(format!("Invalid value {} in column {} of row {}", field, column, count)))
+            };
+            data.push((value(from, &columns.source)?, value(to, &columns.target)?, value(weight, &columns.weight)?));
+        }
+        Ok(self.clean_weighted(data))
+    }
+
+    /// Load from a file containing an NxN adjacency matrix, one row of whitespace separated
+    /// weights per line, where row `i` holds the weights of the edges leaving node `i`.
+    /// Zero entries mean there is no edge. Returns an error if the matrix is not square.
+    pub fn load_adjacency_matrix(&self, filename: &str) -> Result<Vec<WeightedEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut data = Vec::new();
+        let file = self.open_input(filename)?;
+        let lines = file.lines();
+
+        // Every row is checked against the size of the matrix, even if another peer loads it.
+        let mut num_rows = 0;
+        let mut num_nodes = None;
+        for (count, read_line) in lines.enumerate() {
+            let line = read_line?;
+            if self.is_comment(&line) || line.trim().is_empty() {
+                continue;
+            }
+            let row = line
+                .split_whitespace()
+                .map(|weight| parse_field(Some(weight), count + 1))
+                .collect::<Result<Vec<Weight>, GraphLoadError>>()?;
+            let size = *num_nodes.get_or_insert(row.len());
+            if row.len() != size {
+                return Err(GraphLoadError::RowLength { line: count + 1, entries: row.len(), expected: size });
+            }
+            if num_rows % self.peers == self.index {
+                for (to, weight) in row.into_iter().enumerate() {
+                    if weight != 0 {
+                        data.push((num_rows as Node, to as Node, weight));
+                    }
+                }
+            }
+            num_rows += 1;
+        }
+        if num_rows != num_nodes.unwrap_or(0) {
+            return Err(GraphLoadError::NotSquare { rows: num_rows, columns: num_nodes.unwrap_or(0) });
+        }
+        Ok(self.clean_weighted(data))
+    }
+
+    /// Like `load_adjacency_matrix`, but panics with the error.
+    pub fn load_adjacency_matrix_or_panic(&self, filename: &str) -> Vec<WeightedEdge> {
+        self.load_adjacency_matrix(filename).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Load from a file in the METIS graph format. The header is "nodes edges [fmt [ncon]]", and
+    /// then line `i` lists the neighbours of node `i`, 1-indexed, with an empty line for a node
+    /// without neighbours. The digits of `fmt` say whether each line starts with the node size
+    /// and `ncon` node weights, which are skipped, and whether every neighbour is followed by the
+    /// edge weight. Without edge weights every edge has weight 1. Nodes are returned 0-indexed,
+    /// and lines starting with "%" are comments. Returns an error if the file does not list every node.
+    pub fn load_metis(&self, filename: &str) -> Result<Vec<WeightedEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        const HEADER: &str = "nodes edges [fmt [ncon]]";
+        let mut data = Vec::new();
+        let file = self.open_input(filename)?;
+        // Lines are numbered before the comments are skipped, for the errors.
+        let mut lines = file.lines().enumerate().filter(|(_, read_line)| {
+            read_line.as_ref().map_or(true, |line| !line.starts_with('%') && !self.is_comment(line))
+        });
+
+        let (header_line, header) = match lines.next() {
+            Some((count, read_line)) => (count + 1, read_line?),
+            None => return Err(GraphLoadError::InvalidHeader { line: 1, expected: HEADER }),
+        };
+        let header: Vec<&str> = header.split_whitespace().collect();
+        if header.len() < 2 || header.len() > 4 {
+            return Err(GraphLoadError::InvalidHeader { line: header_line, expected: HEADER });
+        }
+        let num_nodes = parse_field(Some(header[0]), header_line)?;
+        // Digits of fmt from the right: edge weights, node weights, node sizes.
+        let fmt = match header.get(2) {
+            Some(fmt) => parse_field(Some(fmt), header_line)?,
+            None => 0,
+        };
+        if fmt % 10 > 1 || fmt / 10 % 10 > 1 || fmt / 100 > 1 {
+            return Err(GraphLoadError::Unsupported { line: header_line, what: format!("METIS fmt {}", header[2]) });
+        }
+        let edge_weights = fmt % 10 == 1;
+        let node_weights = match header.get(3) {
+            _ if fmt / 10 % 10 == 0 => 0,
+            Some(ncon) => parse_field(Some(ncon), header_line)? as usize,
+            None => 1,
+        };
+        let skipped = (fmt / 100) as usize + node_weights;
+
+        let mut num_rows = 0;
+        for (count, read_line) in lines {
+            let line = read_line?;
+            self.report_progress(filename, count + 1);
+            // Only blank lines may follow the last node, other lines are counted for the error.
+            if num_rows >= num_nodes {
+                if !line.trim().is_empty() {
+                    num_rows += 1;
+                }
+                continue;
+            }
+            if num_rows as usize % self.peers == self.index {
+                let mut text = line.split_whitespace().skip(skipped);
+                while let Some(neighbour) = text.next() {
+                    let neighbour = parse_field(Some(neighbour), count + 1)?;
+                    if neighbour == 0 || neighbour > num_nodes {
+                        return Err(GraphLoadError::NodeOutOfRange { line: count + 1, node: neighbour, num_nodes: num_nodes });
+                    }
+                    let weight = if edge_weights { parse_field(text.next(), count + 1)? } else { 1 };
+                    data.push((num_rows, neighbour - 1, weight));
+                }
+            }
+            num_rows += 1;
+        }
+        if num_rows != num_nodes {
+            return Err(GraphLoadError::WrongCount { what: "nodes", found: num_rows as usize, expected: num_nodes as usize });
+        }
+        Ok(self.clean_weighted(data))
+    }
+
+    /// Like `load_metis`, but panics with the error.
+    pub fn load_metis_or_panic(&self, filename: &str) -> Vec<WeightedEdge> {
+        self.load_metis(filename).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Load from a file in the MatrixMarket coordinate format, as used by the SuiteSparse
+    /// collection. After the "%%MatrixMarket" banner and "%" comments, the first line holds the
+    /// dimensions "rows cols entries", and every following line an entry "row col [value]",
+    /// 1-indexed, which becomes an edge from row to col. Values are rounded to weights, and
+    /// `pattern` matrices have weight 1. Of `symmetric` matrices only one triangle is stored, so
+    /// the reverse of every entry off the diagonal is added. Nodes are returned 0-indexed.
+    /// Returns an error if the file does not list as many entries as its dimensions line.
+    pub fn load_matrix_market(&self, filename: &str) -> Result<Vec<WeightedEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        const BANNER: &str = "%%MatrixMarket matrix <format> <field> <symmetry>";
+        const DIMENSIONS: &str = "rows cols entries";
+        let mut data = Vec::new();
+        let file = self.open_input(filename)?;
+        let mut lines = file.lines().enumerate();
+
+        let banner = match lines.next() {
+            Some((_, read_line)) => read_line?,
+            None => return Err(GraphLoadError::InvalidHeader { line: 1, expected: BANNER }),
+        };
+        let banner: Vec<String> = banner.split_whitespace().map(|word| word.to_lowercase()).collect();
+        if banner.len() != 5 || banner[0] != "%%matrixmarket" || banner[1] != "matrix" {
+            return Err(GraphLoadError::InvalidHeader { line: 1, expected: BANNER });
+        }
+        let unsupported = |what: &str, value: &str| GraphLoadError::Unsupported { line: 1, what: format!("MatrixMarket {} {}", what, value) };
+        if banner[2] != "coordinate" {
+            return Err(unsupported("format", &banner[2]));
+        }
+        let pattern = match banner[3].as_str() {
+            "real" | "integer" => false,
+            "pattern" => true,
+            field => return Err(unsupported("field", field)),
+        };
+        let symmetric = match banner[4].as_str() {
+            "general" => false,
+            "symmetric" => true,
+            symmetry => return Err(unsupported("symmetry", symmetry)),
+        };
+
+        // Lines are numbered before the comments are skipped, for the errors.
+        let mut lines = lines.filter(|(_, read_line)| {
+            read_line.as_ref().map_or(true, |line| !line.starts_with('%') && !line.trim().is_empty())
+        });
+        let (dimensions_line, dimensions) = match lines.next() {
+            Some((count, read_line)) => (count + 1, read_line?),
+            None => return Err(GraphLoadError::InvalidHeader { line: 2, expected: DIMENSIONS }),
+        };
+        let dimensions = dimensions
+            .split_whitespace()
+            .map(|size| parse_field(Some(size), dimensions_line))
+            .collect::<Result<Vec<u32>, GraphLoadError>>()?;
+        if dimensions.len() != 3 {
+            return Err(GraphLoadError::InvalidHeader { line: dimensions_line, expected: DIMENSIONS });
+        }
+        let num_nodes = std::cmp::max(dimensions[0], dimensions[1]);
+
+        let mut num_entries = 0;
+        for (count, read_line) in lines {
+            let line = read_line?;
+            self.report_progress(filename, count + 1);
+            if num_entries % self.peers == self.index {
+                let mut text = line.split_whitespace();
+                let from = parse_field(text.next(), count + 1)?;
+                let to = parse_field(text.next(), count + 1)?;
+                for node in [from, to].iter() {
+                    if *node == 0 || *node > num_nodes {
+                        return Err(GraphLoadError::NodeOutOfRange { line: count + 1, node: *node, num_nodes: num_nodes });
+                    }
+                }
+                let weight = if pattern {
+                    1
+                } else {
+                    let value = text.next().ok_or(GraphLoadError::MissingField { line: count + 1 })?;
+                    let value: f64 = value.parse().map_err(|error| GraphLoadError::ParseFloat { line: count + 1, source: error })?;
+                    if value < 0.0 {
+                        return Err(GraphLoadError::Negative { line: count + 1 });
+                    }
+                    value.round() as Weight
+                };
+                data.push((from - 1, to - 1, weight));
+                if symmetric && from != to {
+                    data.push((to - 1, from - 1, weight));
+                }
+            }
+            num_entries += 1;
+        }
+        if num_entries != dimensions[2] as usize {
+            return Err(GraphLoadError::WrongCount { what: "entries", found: num_entries, expected: dimensions[2] as usize });
+        }
+        Ok(self.clean_weighted(data))
+    }
+
+    /// Like `load_matrix_market`, but panics with the error.
+    pub fn load_matrix_market_or_panic(&self, filename: &str) -> Vec<WeightedEdge> {
+        self.load_matrix_market(filename).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Load from a file containing quadruplets of numbers: "source target departure arrival".
+    /// Comments and blank lines are skipped as in `load_weighted_graph`. Reverse connections
+    /// have the same times, and `with_dedup` keeps of the connections with the same source,
+    /// target and departure the one arriving first.
+    pub fn load_temporal_graph(&self, filename: &str) -> Result<Vec<TemporalEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut data = Vec::new();
+        let file = self.open_input(filename)?;
+        let lines = file.lines();
+
+        for (count, read_line) in lines.enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            self.report_progress(filename, count + 1);
+            if count % self.peers == self.index {
+                let line = read_line?;
+                if self.is_comment(&line) || line.trim().is_empty() {
+                    continue;
+                }
+                let mut text = line.split_whitespace();
+                let from = parse_field(text.next(), count + 1)?;
+                let to = parse_field(text.next(), count + 1)?;
+                let departure = parse_field(text.next(), count + 1)?;
+                let arrival = parse_field(text.next(), count + 1)?;
+                if arrival < departure {
+                    return Err(GraphLoadError::ArrivalBeforeDeparture { line: count + 1 });
+                }
+                data.push((from, to, departure, arrival));
+            }
+        }
+        Ok(self.clean_edges(
+            data,
+            |&(from, to, _, _)| (from, to),
+            |&(from, to, departure, arrival)| (to, from, departure, arrival),
+            |&(from, to, departure, _)| (from, to, departure),
+        ))
+    }
+
+    /// Load the topology from a file containing triplets of numbers: "edge_id source target"
+    /// and the weights from a file containing pairs of numbers: "edge_id weight".
+    /// Weights are matched to edges by ID, so the two files may list edges in any order.
+    /// Comments and blank lines are skipped as in `load_weighted_graph`, and line numbers in
+    /// errors are lines of the file the error was found in.
+    pub fn load_weighted_graph_by_edge_id(&self, topology_filename: &str, weights_filename: &str) -> Result<Vec<WeightedEdge>, GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+        use std::collections::HashMap;
+
+        // Every worker needs all the weights, as its edges can have any ID.
+        let mut weights: HashMap<u32, Weight> = HashMap::new();
+        let file = self.open_input(weights_filename)?;
+        for (count, read_line) in file.lines().enumerate() {
+            let line = read_line?;
+            if self.is_comment(&line) || line.trim().is_empty() {
+                continue;
+            }
+            let mut text = line.split_whitespace();
+            let id = parse_field(text.next(), count + 1)?;
+            let weight = parse_field(text.next(), count + 1)?;
+            if weights.insert(id, weight).is_some() {
+                return Err(GraphLoadError::DuplicateEdgeId { line: count + 1, id: id });
+            }
+        }
+
+        let mut data = Vec::new();
+        let mut seen_ids = std::collections::HashSet::new();
+        let file = self.open_input(topology_filename)?;
+        let lines = file.lines();
+
+        for (count, read_line) in lines.enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            self.report_progress(topology_filename, count + 1);
+            if count % self.peers == self.index {
+                let line = read_line?;
+                if self.is_comment(&line) || line.trim().is_empty() {
+                    continue;
+                }
+                let mut text = line.split_whitespace();
+                let id = parse_field(text.next(), count + 1)?;
+                let from = parse_field(text.next(), count + 1)?;
+                let to = parse_field(text.next(), count + 1)?;
+                if !seen_ids.insert(id) {
+                    return Err(GraphLoadError::DuplicateEdgeId { line: count + 1, id: id });
+                }
+                let weight = *weights.get(&id).ok_or(GraphLoadError::MissingEdgeId { line: count + 1, id: id })?;
+                data.push((from, to, weight));
+            }
+        }
+        Ok(self.clean_weighted(data))
+    }
+
+    /// Load from a file in the binary format written by `write_binary_graph`.
+    pub fn load_binary_graph(&self, filename: &str) -> Vec<WeightedEdge> {
+        self.read_binary_graph(filename).expect("Could not read binary graph file")
+    }
+
+    fn read_binary_graph(&self, filename: &str) -> std::io::Result<Vec<WeightedEdge>> {
+        // Standard io/fs boilerplate.
+        use std::io::Read;
+
+        let mut file = self.open_input(filename)?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        let num_edges = u64::from_le_bytes(header);
+
+        let mut data = Vec::new();
+        let mut record = [0u8; 12];
+        for count in 0 .. num_edges {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            file.read_exact(&mut record)?;
+            if count as usize % self.peers == self.index {
+                let field = |i: usize| u32::from_le_bytes([record[i], record[i + 1], record[i + 2], record[i + 3]]);
+                data.push((field(0), field(4), field(8)));
+            }
+        }
+        Ok(self.clean_weighted(data))
+    }
+
+    /// Like `load_weighted_graph`, but keeps the parsed edges in a binary cache file at
+    /// `cache_path`, in the format of `write_binary_graph`. The cache is read instead of the
+    /// text file if it was modified no earlier than it. Otherwise the whole text file is
+    /// parsed and the cache written, before this peer takes its share of the edges, so peers
+    /// sharing a cache agree on it. Isolated node declarations are not kept in the cache.
+    /// The cache holds the edges after `with_symmetric`, `with_dedup` and `with_drop_self_loops`,
+    /// so it must be read back with the same settings.
+    pub fn load_weighted_graph_cached(&self, txt_path: &str, cache_path: &str) -> Result<Vec<WeightedEdge>, GraphLoadError> {
+        let source_modified = std::fs::metadata(txt_path)?.modified()?;
+        let cache_fresh = match std::fs::metadata(cache_path).and_then(|metadata| metadata.modified()) {
+            Ok(cache_modified) => cache_modified >= source_modified,
+            Err(_) => false,
+        };
+        if cache_fresh {
+            let cached = GraphLoader { dedup: false, drop_self_loops: false, symmetric: false, comment_prefixes: Vec::new(), ..*self };
+            return Ok(cached.read_binary_graph(cache_path)?);
+        }
+
+        // The cache always holds the whole graph, and the limit applies to this peer's share.
+        let whole = GraphLoader { index: 0, peers: 1, comment_prefixes: self.comment_prefixes.clone(), limit: None, ..*self };
+        let edges = whole.load_weighted_graph(txt_path)?;
+        // Written under another name first, so no peer reads a partly written cache.
+        let partial_path = format!("{}.partial.{}", cache_path, self.index);
+        write_binary_graph(&edges, &partial_path)?;
+        std::fs::rename(&partial_path, cache_path)?;
+        let share = edges
+            .into_iter()
+            .enumerate()
+            .filter(|(count, _)| count % self.peers == self.index)
+            .map(|(_, edge)| edge)
+            .take(self.peer_limit().unwrap_or(usize::max_value()))
+            .collect();
+        Ok(share)
+    }
+
+    /// Load from a file containing pairs of numbers: "source target"
+    pub fn load_unweighted_graph(&self, filename: &str) -> Result<Vec<UnweightedEdge>, GraphLoadError> {
+        Ok(self.load_unweighted_graph_with_nodes(filename)?.0)
+    }
+
+    /// Like `load_unweighted_graph`, but panics with the error.
+    pub fn load_unweighted_graph_or_panic(&self, filename: &str) -> Vec<UnweightedEdge> {
+        self.load_unweighted_graph(filename).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Load from a file containing pairs of numbers: "source target",
+    /// and lines with a single number declaring an isolated node, which are returned separately.
+    /// Blank lines are skipped.
+    pub fn load_unweighted_graph_with_nodes(&self, filename: &str) -> Result<(Vec<UnweightedEdge>, Vec<Node>), GraphLoadError> {
+        // Standard io/fs boilerplate.
+        use std::io::BufRead;
+
+        let mut data = Vec::new();
+        let mut isolated = Vec::new();
+        let file = self.open_input(filename)?;
+        let lines = file.lines();
+
+        for (count, read_line) in lines.enumerate() {
+            if self.limit_reached(data.len()) {
+                break;
+            }
+            self.report_progress(filename, count + 1);
+            if count % self.peers == self.index {
+                let line = read_line?;
+                if self.is_comment(&line) || line.trim().is_empty() {
+                    continue;
+                }
+                let mut text = line.split_whitespace();
+                let from = parse_field(text.next(), count + 1)?;
+                let to = match text.next() {
+                    Some(to) => parse_field(Some(to), count + 1)?,
+                    None => {
+                        isolated.push(from);
+                        continue;
+                    }
+                };
+                data.push((from, to));
+            }
+        }
+        Ok((self.clean_unweighted(data), isolated))
+    }
+}
+
+pub use rand::SeedableRng;
+
+pub fn default_rng(seed: u64) -> rand_chacha::ChaCha8Rng {
+    rand_chacha::ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// Multiply every weight by `factor`, rounding to the nearest weight. Weights too large
+/// for a `Weight` saturate.
+pub fn scale_weights(edges: Vec<WeightedEdge>, factor: f32) -> Vec<WeightedEdge> {
+    edges
+        .into_iter()
+        .map(|(from, to, weight)| (from, to, (weight as f64 * factor as f64).round() as Weight))
+        .collect()
+}
+
+/// The edges followed by the reverse `(v, u, w)` of every edge `(u, v, w)` that is not a self loop.
+pub fn symmetrize_edges(mut edges: Vec<WeightedEdge>) -> Vec<WeightedEdge> {
+    let reverse: Vec<WeightedEdge> = edges.iter().filter(|(from, to, _)| from != to).map(|&(from, to, weight)| (to, from, weight)).collect();
+    edges.extend(reverse);
+    edges
+}
+
+/// Generate a random graph with a given number of vertices and edges
+pub fn generate_unweighted_graph(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, num_edges: u32) -> Vec<UnweightedEdge> {
+    use rand::distributions::{Distribution, Uniform};
+
+    let dist = Uniform::new(0 as Node, num_nodes as Node);
+    let mut edges = Vec::new();
+    for _ in 0 .. num_edges {
+        let from = dist.sample(rng);
+        let to = dist.sample(rng);
+        edges.push((from, to));
+    }
+    edges
+}
+
+/// Generate a random graph that is weakly connected: a random spanning tree over all nodes,
+/// each attached to a random earlier node of a random order with an edge in a random
+/// direction, followed by `num_edges - (num_nodes - 1)` uniformly random edges.
+/// Panics if there are fewer edges than the spanning tree needs.
+pub fn generate_connected_graph(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, num_edges: u32) -> Vec<UnweightedEdge> {
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+
+    let tree_edges = num_nodes.saturating_sub(1);
+    assert!(num_edges >= tree_edges, "A connected graph on {} nodes needs at least {} edges", num_nodes, tree_edges);
+    let mut order: Vec<Node> = (0 .. num_nodes).collect();
+    order.shuffle(rng);
+    let mut edges = Vec::with_capacity(num_edges as usize);
+    for index in 1 .. order.len() {
+        let parent = order[rng.gen_range(0, index)];
+        let node = order[index];
+        edges.push(if rng.gen() { (parent, node) } else { (node, parent) });
+    }
+    edges.extend(generate_unweighted_graph(rng, num_nodes, num_edges - tree_edges));
+    edges
+}
+
+/// Generate an Erdős–Rényi G(n, p) random graph: every unordered pair of distinct nodes is
+/// connected with probability `p`, independently. The result is a simple graph, with each
+/// undirected edge listed in both directions. Pairs without an edge are skipped over with
+/// geometrically distributed jumps, so sparse graphs take time proportional to their size.
+pub fn generate_erdos_renyi(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, p: f64) -> Vec<UnweightedEdge> {
+    use rand::Rng;
+
+    let mut edges = Vec::new();
+    if p <= 0.0 {
+        return edges;
+    }
+    // Pairs (v, w) with w < v are enumerated row by row.
+    let log_q = (1.0 - p).ln();
+    let (mut v, mut w): (u64, i64) = (1, -1);
+    while v < num_nodes as u64 {
+        let skip = if p >= 1.0 { 0 } else { ((1.0 - rng.gen::<f64>()).ln() / log_q).floor() as i64 };
+        w += 1 + skip;
+        while w >= v as i64 && v < num_nodes as u64 {
+            w -= v as i64;
+            v += 1;
+        }
+        if v < num_nodes as u64 {
+            edges.push((v as Node, w as Node));
+            edges.push((w as Node, v as Node));
+        }
+    }
+    edges
+}
+
+/// Generate a Barabási–Albert preferential attachment graph, which has a power-law degree
+/// distribution. It starts with `m` seed nodes, and every further node attaches to `m` distinct
+/// existing nodes, chosen with probability proportional to their degree. The first attached
+/// node connects to all seed nodes. Each undirected edge is listed in both directions.
+pub fn generate_barabasi_albert(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, m: u32) -> Vec<UnweightedEdge> {
+    use rand::Rng;
+
+    let mut edges = Vec::new();
+    // Every node appears once per incident edge, so sampling from this list
+    // picks a node with probability proportional to its degree.
+    let mut endpoints: Vec<Node> = Vec::new();
+    for node in m..num_nodes {
+        let mut targets: Vec<Node> = Vec::new();
+        if endpoints.is_empty() {
+            targets.extend(0..m);
+        } else {
+            while targets.len() < m as usize {
+                let target = endpoints[rng.gen_range(0, endpoints.len())];
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+        }
+        for target in targets.into_iter() {
+            edges.push((node, target));
+            edges.push((target, node));
+            endpoints.push(node);
+            endpoints.push(target);
+        }
+    }
+    edges
+}
+
+/// Generate a Watts–Strogatz small-world graph: a ring lattice where every node is connected
+/// to its `k` nearest neighbours, `k / 2` on each side, whose edges are then rewired with
+/// probability `beta` each. A rewired edge keeps its first node and gets a uniformly random
+/// other end, avoiding self loops and duplicate edges. Each undirected edge is listed in both
+/// directions. `k` must be even and less than `num_nodes`.
+pub fn generate_watts_strogatz(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, k: u32, beta: f64) -> Vec<UnweightedEdge> {
+    use rand::Rng;
+    use std::collections::HashSet;
+
+    let key = |a: Node, b: Node| (std::cmp::min(a, b), std::cmp::max(a, b));
+    let mut present: HashSet<(Node, Node)> = HashSet::new();
+    let mut degree = vec![k; num_nodes as usize];
+    let mut lattice = Vec::new();
+    for node in 0..num_nodes {
+        for offset in 1..=k / 2 {
+            let neighbour = (node + offset) % num_nodes;
+            present.insert(key(node, neighbour));
+            lattice.push((node, neighbour));
+        }
+    }
+    let mut edges = Vec::with_capacity(2 * lattice.len());
+    for (node, neighbour) in lattice.into_iter() {
+        let mut target = neighbour;
+        // A node already connected to all others has nothing to rewire to.
+        if rng.gen::<f64>() < beta && degree[node as usize] < num_nodes - 1 {
+            loop {
+                let candidate = rng.gen_range(0, num_nodes);
+                if candidate != node && !present.contains(&key(node, candidate)) {
+                    present.remove(&key(node, neighbour));
+                    present.insert(key(node, candidate));
+                    degree[neighbour as usize] -= 1;
+                    degree[candidate as usize] += 1;
+                    target = candidate;
+                    break;
+                }
+            }
+        }
+        edges.push((node, target));
+        edges.push((target, node));
+    }
+    edges
+}
+
+/// Generate a `rows` by `cols` grid, where node `r * cols + c` is connected to the nodes above,
+/// below, left and right of it. Each undirected edge is listed in both directions, so with unit
+/// weights the distance between two nodes is their Manhattan distance.
+pub fn generate_grid_graph(rows: u32, cols: u32) -> Vec<UnweightedEdge> {
+    let mut edges = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            let node = r * cols + c;
+            if c + 1 < cols {
+                edges.push((node, node + 1));
+                edges.push((node + 1, node));
+            }
+            if r + 1 < rows {
+                edges.push((node, node + cols));
+                edges.push((node + cols, node));
+            }
+        }
+    }
+    edges
+}
+
+/// Generate a Graph500 style R-MAT graph with `2^scale` nodes and `edge_factor * 2^scale`
+/// directed edges. Each edge picks one of the four quadrants of the adjacency matrix with
+/// the probabilities `(a, b, c, d)`, which sum to 1, and recurses into it once per bit of the
+/// node indices. `b` sets a bit of the target, `c` of the source and `d` of both, so a large
+/// `a` concentrates the edges on low indices and gives a skewed degree distribution.
+pub fn generate_rmat(rng: &mut rand_chacha::ChaCha8Rng, scale: u32, edge_factor: u32, probabilities: (f64, f64, f64, f64)) -> Vec<UnweightedEdge> {
+    use rand::Rng;
+
+    let (a, b, c, _d) = probabilities;
+    let num_edges = (edge_factor as u64) << scale;
+    let mut edges = Vec::with_capacity(num_edges as usize);
+    for _ in 0 .. num_edges {
+        let (mut from, mut to) = (0 as Node, 0 as Node);
+        for bit in 0 .. scale {
+            let r: f64 = rng.gen();
+            if r < a {
+                continue;
+            } else if r < a + b {
+                to |= 1 << bit;
+            } else if r < a + b + c {
+                from |= 1 << bit;
+            } else {
+                from |= 1 << bit;
+                to |= 1 << bit;
+            }
+        }
+        edges.push((from, to));
+    }
+    edges
+}
+
+/// Generate a random graph with a given number of vertices, edges and weights for the edges.
+pub fn generate_weighted_graph(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, num_edges: u32, weight_par: &WeightParameters) -> Vec<WeightedEdge> {
+    use rand::distributions::{Distribution, Uniform};
+
+    let dist = Uniform::new(0 as Node, num_nodes as Node);
+    let dist_w = WeightSampler::new(weight_par);
+    let mut edges = Vec::new();
+    for _ in 0 .. num_edges {
+        let from = dist.sample(rng);
+        let to = dist.sample(rng);
+        let w = dist_w.sample(rng);
+        edges.push((from, to, w));
+    }
+    edges
+}
+
+/// Generate a bipartite graph of user-item interactions. Users have the IDs `0..num_users`
+/// and items the IDs `num_users..num_users + num_items`. Each interaction picks a user
+/// uniformly and an item with probability proportional to `1 + skew * degree`, so popular
+/// items attract more interactions. Interactions are added as edges in both directions.
+pub fn generate_bipartite_graph(rng: &mut rand_chacha::ChaCha8Rng, num_users: u32, num_items: u32, num_interactions: u32, skew: f64, weight_par: &WeightParameters) -> Vec<WeightedEdge> {
+    use rand::distributions::{Distribution, Uniform};
+    use rand::Rng;
+
+    let dist_user = Uniform::new(0 as Node, num_users as Node);
+    let dist_item = Uniform::new(num_users as Node, (num_users + num_items) as Node);
+    let dist_w = WeightSampler::new(weight_par);
+    // Every item appears once per interaction, so sampling from this list
+    // picks an item with probability proportional to its degree.
+    let mut item_endpoints: Vec<Node> = Vec::new();
+    let mut edges = Vec::new();
+    for _ in 0 .. num_interactions {
+        let user = dist_user.sample(rng);
+        let preferential = skew * item_endpoints.len() as f64;
+        let item = if rng.gen_bool(preferential / (num_items as f64 + preferential)) {
+            item_endpoints[rng.gen_range(0, item_endpoints.len())]
+        } else {
+            dist_item.sample(rng)
+        };
+        item_endpoints.push(item);
+        let w = dist_w.sample(rng);
+        edges.push((user, item, w));
+        edges.push((item, user, w));
+    }
+    edges
+}
+
+/// Greedy spanner: a subgraph in which every shortest path is at most `stretch` times
+/// longer than in the original graph. Edges are considered by increasing weight and kept
+/// only if the spanner built so far has no path between their endpoints within the stretch.
+pub fn build_spanner(edges: &[WeightedEdge], stretch: f64) -> Vec<WeightedEdge> {
+    use std::cmp::Reverse;
+    use std::collections::{BinaryHeap, HashMap};
+
+    let mut sorted = edges.to_vec();
+    sorted.sort_by_key(|&(from, to, w)| (w, from, to));
+    let mut adjacency: HashMap<Node, Vec<(Node, Weight)>> = HashMap::new();
+    let mut spanner = Vec::new();
+    for (from, to, w) in sorted.into_iter() {
+        // Self loops are never part of a shortest path.
+        if from == to {
+            continue;
+        }
+        // Dijkstra search in the spanner, cut off at the allowed path length.
+        let limit = stretch * w as f64;
+        let mut distances: HashMap<Node, u64> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        distances.insert(from, 0);
+        heap.push(Reverse((0u64, from)));
+        let mut covered = false;
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            if node == to {
+                covered = true;
+                break;
+            }
+            if cost > distances[&node] {
+                continue;
+            }
+            for &(next, weight) in adjacency.get(&node).map(|n| n.as_slice()).unwrap_or(&[]) {
+                let next_cost = cost + weight as u64;
+                if next_cost as f64 <= limit && distances.get(&next).map_or(true, |&d| next_cost < d) {
+                    distances.insert(next, next_cost);
+                    heap.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+        if !covered {
+            adjacency.entry(from).or_insert_with(Vec::new).push((to, w));
+            spanner.push((from, to, w));
+        }
+    }
+    spanner
+}
+
+/// Number of shortest paths from the `sources` that use each edge, indexed by its position in `edges`.
+/// Every reached node picks one tight incoming edge (`distance[from] + weight == distance[to]`)
+/// in a breadth first search from the sources, and the edge is used by the paths to all nodes
+/// in the shortest path tree below it.
+pub fn shortest_path_usage(edges: &[WeightedEdge], distances: &std::collections::HashMap<Node, Weight>, sources: &[Node]) -> Vec<u32> {
+    use std::collections::{HashMap, VecDeque};
+
+    let mut tight: HashMap<Node, Vec<(Node, usize)>> = HashMap::new();
+    for (id, &(from, to, w)) in edges.iter().enumerate() {
+        if let (Some(&d_from), Some(&d_to)) = (distances.get(&from), distances.get(&to)) {
+            if d_from as u64 + w as u64 == d_to as u64 {
+                tight.entry(from).or_insert_with(Vec::new).push((to, id));
+            }
+        }
+    }
+    // Shortest path tree, as the parent edge of every node in the order they were reached.
+    let mut parent: HashMap<Node, Option<usize>> = HashMap::new();
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+    for &source in sources.iter() {
+        if !parent.contains_key(&source) {
+            parent.insert(source, None);
+            queue.push_back(source);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for &(next, id) in tight.get(&node).map(|n| n.as_slice()).unwrap_or(&[]) {
+            if !parent.contains_key(&next) {
+                parent.insert(next, Some(id));
+                queue.push_back(next);
+            }
+        }
+    }
+    // Subtree sizes, accumulated from the last reached nodes back to the sources.
+    let mut usage = vec![0u32; edges.len()];
+    let mut subtree: HashMap<Node, u32> = HashMap::new();
+    for node in order.into_iter().rev() {
+        let size = 1 + subtree.get(&node).cloned().unwrap_or(0);
+        if let Some(id) = parent[&node] {
+            usage[id] = size;
+            *subtree.entry(edges[id].0).or_insert(0) += size;
+        }
+    }
+    usage
+}
+
+/// Nodes whose distance differs from Dijkstra's algorithm, as (node, computed, expected)
+/// sorted by node, where `None` means unreachable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistanceMismatches(pub Vec<(Node, Option<u64>, Option<u64>)>);
+
+impl std::fmt::Display for DistanceMismatches {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} distances differ from Dijkstra, the first as (node, computed, expected): {:?}",
+            self.0.len(),
+            &self.0[.. std::cmp::min(10, self.0.len())]
+        )
+    }
+}
+
+impl std::error::Error for DistanceMismatches {}
+
+/// Compare the distances of a computation with those of Dijkstra's algorithm on the same
+/// edges. Nodes missing from `distances` are taken to be unreachable. Returns the number of
+/// compared nodes, or the nodes that disagree.
+pub fn validate_distances(edges: &[WeightedEdge], sources: &[Node], distances: &std::collections::HashMap<Node, u64>) -> Result<usize, DistanceMismatches> {
+    let num_nodes = sources.iter().map(|source| source + 1).max().unwrap_or(0);
+    let adjacency = adjacency_list(edges, num_nodes);
+    let expected = dijkstra(&adjacency, sources);
+    let mut mismatches = Vec::new();
+    for (node, expected) in expected.iter().enumerate() {
+        let actual = distances.get(&(node as Node)).cloned();
+        if actual != *expected {
+            mismatches.push((node as Node, actual, *expected));
+        }
+    }
+    for (node, distance) in distances.iter() {
+        if *node as usize >= expected.len() {
+            mismatches.push((*node, Some(*distance), None));
+        }
+    }
+    if !mismatches.is_empty() {
+        mismatches.sort();
+        return Err(DistanceMismatches(mismatches));
+    }
+    Ok(expected.len())
+}
+
+/// Lists of the `(to, weight)` pairs of the edges leaving each node, indexed by node.
+/// There is a list for each of the `num_nodes` nodes, and more if the edges have larger IDs.
+pub fn adjacency_list(edges: &[WeightedEdge], num_nodes: u32) -> Vec<Vec<(Node, Weight)>> {
+    let num_nodes = std::cmp::max(num_nodes, num_nodes_from_edge_list(edges));
+    let mut adjacency = vec![Vec::new(); num_nodes as usize];
+    for &(from, to, weight) in edges.iter() {
+        adjacency[from as usize].push((to, weight));
+    }
+    adjacency
+}
+
+/// Distance of every node from the nearest of the `sources` with Dijkstra's algorithm on a
+/// binary heap, or `None` for unreachable nodes. Weights must be non-negative, which `u32`
+/// weights always are. Distances are summed as `u64`, so long paths do not overflow.
+pub fn dijkstra(adjacency: &[Vec<(Node, Weight)>], sources: &[Node]) -> Vec<Option<u64>> {
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    let mut distances = vec![None; adjacency.len()];
+    let mut heap = BinaryHeap::new();
+    for &source in sources.iter() {
+        distances[source as usize] = Some(0);
+        heap.push(Reverse((0u64, source)));
+    }
+    while let Some(Reverse((distance, node))) = heap.pop() {
+        // Skip stale entries of nodes that were already reached by a shorter path.
+        if distances[node as usize].map_or(false, |best| distance > best) {
+            continue;
+        }
+        for (to, weight) in adjacency[node as usize].iter() {
+            let candidate = distance + *weight as u64;
+            if distances[*to as usize].map_or(true, |best| candidate < best) {
+                distances[*to as usize] = Some(candidate);
+                heap.push(Reverse((candidate, *to)));
+            }
+        }
+    }
+    distances
+}
+
+/// Relabel the nodes with a random permutation of the node IDs, which breaks any locality
+/// in the original ID assignment. Returns the relabelled edges and the mapping from the
+/// original to the new IDs, for translating the search query.
+pub fn permute_node_ids(rng: &mut rand_chacha::ChaCha8Rng, edges: Vec<WeightedEdge>) -> (Vec<WeightedEdge>, std::collections::HashMap<Node, Node>) {
+    use rand::seq::SliceRandom;
+
+    let num_nodes = num_nodes_from_edge_list(&edges);
+    let mut permutation: Vec<Node> = (0 .. num_nodes).collect();
+    permutation.shuffle(rng);
+    let mapping: std::collections::HashMap<Node, Node> = (0 .. num_nodes).zip(permutation.into_iter()).collect();
+    let edges = edges.into_iter().map(|(from, to, w)| (mapping[&from], mapping[&to], w)).collect();
+    (edges, mapping)
+}
+
+/// Sample distinct nodes uniformly from `0 .. num_nodes`, all of them if fewer than `amount`.
+pub fn sample_nodes(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, amount: u32) -> Vec<Node> {
+    let amount = std::cmp::min(num_nodes, amount);
+    rand::seq::index::sample(rng, num_nodes as usize, amount as usize)
+        .into_iter()
+        .map(|node| node as Node)
+        .collect()
+}
+
+pub fn generate_weights_for_graph(rng: &mut rand_chacha::ChaCha8Rng, edges: Vec<UnweightedEdge>, weight_par: &WeightParameters) -> Vec<WeightedEdge> {
+    let dist_w = WeightSampler::new(weight_par);
+    edges.into_iter().map(|(from, to)| (from, to,  dist_w.sample(rng))).collect()
+}
+
+/// Samples weights from the distribution of the weight parameters, clamped into the
+/// weight range `low..high`. An empty range `low..low` gives every edge the weight `low`,
+/// whatever the distribution.
+enum WeightSampler {
+    Constant(Weight),
+    Uniform(rand::distributions::Uniform<Weight>),
+    Normal { mean: f64, std: f64, low: Weight, high: Weight },
+    /// Index `k` of the range, so the weight `low + k`, has probability proportional to `(k + 1)^-exponent`.
+    Zipf { index: rand::distributions::WeightedIndex<f64>, low: Weight },
+}
+
+impl WeightSampler {
+    fn new(weight_par: &WeightParameters) -> WeightSampler {
+        let (low, high) = weight_par.weight_range;
+        if low == high {
+            return WeightSampler::Constant(low);
+        }
+        match weight_par.distribution {
+            WeightDist::Uniform => WeightSampler::Uniform(rand::distributions::Uniform::new(low, high)),
+            WeightDist::Normal { mean, std } => WeightSampler::Normal { mean: mean, std: std, low: low, high: high },
+            WeightDist::Zipf { exponent } => {
+                let weights = (0 .. high - low).map(|k| ((k + 1) as f64).powf(-exponent));
+                let index = rand::distributions::WeightedIndex::new(weights).expect("Zipf weights must be positive");
+                WeightSampler::Zipf { index: index, low: low }
+            }
+        }
+    }
+
+    fn sample(&self, rng: &mut rand_chacha::ChaCha8Rng) -> Weight {
+        use rand::distributions::Distribution;
+        use rand::Rng;
+
+        match self {
+            WeightSampler::Constant(weight) => *weight,
+            WeightSampler::Uniform(dist_w) => dist_w.sample(rng),
+            WeightSampler::Normal { mean, std, low, high } => {
+                // Box-Muller transform, with the first sample in (0, 1] for the logarithm.
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (mean + std * z).round().max(*low as f64).min((*high - 1) as f64) as Weight
+            }
+            WeightSampler::Zipf { index, low } => low + index.sample(rng) as Weight,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum GraphDataType {
+    Random, RealWorld, Saved, Bipartite, ErdosRenyi, BarabasiAlbert, Grid, Rmat, WattsStrogatz
+}
+
+/// Distribution of generated weights. Sampled weights are clamped into the weight range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeightDist {
+    Uniform,
+    Normal { mean: f64, std: f64 },
+    /// The lowest weight of the range is the most likely, with probabilities falling off
+    /// as `rank^-exponent`.
+    Zipf { exponent: f64 },
+}
+
+/// Parses `uniform`, `normal:<mean>,<std>` or `zipf:<exponent>`.
+impl std::str::FromStr for WeightDist {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<WeightDist, String> {
+        let mut parts = text.splitn(2, ':');
+        let name = parts.next().unwrap_or("");
+        let parameters: Vec<f64> = match parts.next() {
+            Some(parameters) => parameters
+                .split(',')
+                .map(|x| x.parse().map_err(|_| format!("Invalid weight distribution parameter {:?}", x)))
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        match (name, parameters.as_slice()) {
+            ("uniform", []) => Ok(WeightDist::Uniform),
+            ("normal", [mean, std]) if *std >= 0.0 => Ok(WeightDist::Normal { mean: *mean, std: *std }),
+            ("zipf", [exponent]) if *exponent >= 0.0 => Ok(WeightDist::Zipf { exponent: *exponent }),
+            _ => Err(format!("Invalid weight distribution {:?}", text)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct WeightParameters {
+    pub weight_range: (u32, u32),
+    pub rng_seed: u64,
+    pub distribution: WeightDist,
+}
+
+/// Where the weights of a real world graph come from.
+#[derive(Clone, Copy, Debug)]
+pub enum WeightMode {
+    /// The weights in the file.
+    UseFile,
+    /// Weights generated for a file of unweighted edges.
+    Generate(WeightParameters),
+    /// The weights in the file multiplied by a factor and rounded, for example to convert units.
+    ScaleFile(f32),
+}
+
+#[derive(Clone, Debug)]
+pub enum GraphBenchmarkData {
+   /// With `connected`, the edges start with a spanning tree, see `generate_connected_graph`.
+   RandomGraph { nodes: u32, edges: u32, connected: bool, weight_par: WeightParameters },
+   RealWorldGraph { path_to_edge_list: String, weight_mode: WeightMode },
+   SavedGraph { path_to_binary: String, weight_par: WeightParameters },
+   /// Users and items in disjoint ID ranges, with preferential attachment on the item side.
+   BipartiteGraph { users: u32, items: u32, edges: u32, skew: f64, weight_par: WeightParameters },
+   /// Every pair of nodes is connected with probability `p`.
+   ErdosRenyiGraph { nodes: u32, p: f64, weight_par: WeightParameters },
+   /// Every new node attaches to `m` existing nodes with probability proportional to their degree.
+   BarabasiAlbertGraph { nodes: u32, m: u32, weight_par: WeightParameters },
+   /// Node `r * cols + c` is connected to its neighbours in a `rows` by `cols` grid.
+   GridGraph { rows: u32, cols: u32, weight_par: WeightParameters },
+   /// `2^scale` nodes and `edge_factor * 2^scale` edges drawn by the recursive matrix method
+   /// with the quadrant probabilities `(a, b, c, d)`.
+   RmatGraph { scale: u32, edge_factor: u32, probabilities: (f64, f64, f64, f64), weight_par: WeightParameters },
+   /// A ring lattice of `k` nearest neighbours with each edge rewired with probability `beta`.
+   WattsStrogatzGraph { nodes: u32, k: u32, beta: f64, weight_par: WeightParameters },
+}
+
+#[derive(Clone, Debug)]
+pub enum GraphBenchmarkUpdates {
+    /// Random edges, of which a `deletion_ratio` fraction are instead deletions of present edges.
+    RandomUpdates { edges_per_update: u32, weight_par: WeightParameters, deletion_ratio: f32 },
+    /// New edges with the lowest weight leaving the nodes closest to the source,
+    /// which shorten the distances of many nodes at once.
+    AdversarialUpdates { edges_per_update: u32, weight_par: WeightParameters },
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchQuery {
+    /// The first of `sources`, for the benchmarks that only take one source.
+    pub source: u32,
+    pub sources: Vec<u32>,
+    pub target: u32,
+    /// The target was given as `all`: report the number of reached nodes and the largest
+    /// distance instead of the distance of `target`, which is then 0.
+    pub all_targets: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct BenchmarkDescription {
+    pub graph_data: GraphBenchmarkData,
+    pub graph_updates: GraphBenchmarkUpdates,
+    pub num_rounds: u32,
+    pub warmup_rounds: u32,
+    pub search_query: SearchQuery,
+    /// Seed of the graph generator, which draws the graph, its weights and the updates.
+    pub seed: u64,
+    pub inspect_results: bool,
+    /// Only check the arguments, given as `validate` in place of the inspect string.
+    pub dry_run: bool,
+    pub trace_output: Option<String>,
+    pub turn_penalties: Option<String>,
+    pub dense_out: Option<String>,
+    pub count_records: bool,
+    pub count_reachable: bool,
+    pub permute_nodes: bool,
+    pub compare_recompute: bool,
+    pub convergence_tail: bool,
+    pub reconstruct_path: bool,
+    pub check_distances: bool,
+    pub parallel_loading: bool,
+    pub cpu_time: bool,
+    pub memory: bool,
+    /// Add the reverse of every edge of the initial graph and the updates.
+    pub symmetric: bool,
+    /// Whether the graph is directed, which is the default. `--undirected` makes it undirected
+    /// and also sets `symmetric`. Binaries that can use undirected graphs natively, as
+    /// `sssp_petgraph` does, load the edges once and follow them both ways.
+    pub directed: bool,
+    /// Load the weights of the real world graph as `SignedWeight`.
+    pub signed_weights: bool,
+    pub congestion_factor: Option<f64>,
+    pub dataflow_dot: Option<String>,
+    pub graph_dot: Option<String>,
+    pub budget: Option<Weight>,
+    pub iteration_snapshots: Option<String>,
+    pub max_distance: Option<Weight>,
+    /// Bits of the iteration counter of `sssp_differential_monoid`, 16 or 32.
+    pub iteration_width: u32,
+    pub batch_sizes: Option<Vec<u32>>,
+    pub node_attributes: Option<String>,
+    pub attribute_values: Option<std::collections::HashSet<String>>,
+    pub spanner_stretch: Option<f64>,
+    pub num_paths: usize,
+    pub save_graph: Option<String>,
+    pub sample_sources: Option<u32>,
+    pub sample_seed: u64,
+    pub percentile: f64,
+    pub start_time: u32,
+    pub iterations: u32,
+    pub delta: Option<Weight>,
+    pub round_changes: Option<usize>,
+    /// Number of update rounds whose changes are fed in before the input is advanced and
+    /// the computation catches up, timed as one batch.
+    pub rounds_per_batch: u32,
+}
+
+impl BenchmarkDescription {
+    /// Whether `round`, counting the warmup rounds first, is the last round of its batch.
+    /// Warmup and measured rounds are batched separately, and the last round of each
+    /// always ends a batch, which may then be shorter.
+    pub fn ends_batch(&self, round: u32) -> bool {
+        let (phase_round, phase_rounds) = if round < self.warmup_rounds {
+            (round, self.warmup_rounds)
+        } else {
+            (round - self.warmup_rounds, self.num_rounds)
+        };
+        (phase_round + 1) % self.rounds_per_batch == 0 || phase_round + 1 == phase_rounds
+    }
+}
+
+pub fn extract_weight_range(data: &GraphBenchmarkData) -> (u32, u32) {
+    extract_weight_parameters(data).weight_range
+}
+
+/// Whether `GraphDataGenerator::gen_initial_graph_partitioned` can split the data between workers.
+fn supports_partitioned_loading(data: &GraphBenchmarkData) -> bool {
+    use GraphBenchmarkData::*;
+    match data {
+        RandomGraph { connected: false, .. } => true,
+        // Standard input cannot be split between the workers.
+        SavedGraph { path_to_binary: path, .. }
+        | RealWorldGraph { path_to_edge_list: path, weight_mode: WeightMode::UseFile }
+        | RealWorldGraph { path_to_edge_list: path, weight_mode: WeightMode::ScaleFile(_) } => path != STDIN_FILENAME,
+        _ => false,
+    }
+}
+
+/// The weight parameters of the data, unless it is a real world graph with its own weights.
+fn weight_parameters_mut(data: &mut GraphBenchmarkData) -> Option<&mut WeightParameters> {
+    use GraphBenchmarkData::*;
+    match data {
+        RandomGraph{weight_par, ..} => Some(weight_par),
+        RealWorldGraph{weight_mode: WeightMode::Generate(weight_par), ..} => Some(weight_par),
+        RealWorldGraph{..} => None,
+        SavedGraph{weight_par, ..} => Some(weight_par),
+        BipartiteGraph{weight_par, ..} => Some(weight_par),
+        ErdosRenyiGraph{weight_par, ..} => Some(weight_par),
+        BarabasiAlbertGraph{weight_par, ..} => Some(weight_par),
+        GridGraph{weight_par, ..} => Some(weight_par),
+        RmatGraph{weight_par, ..} => Some(weight_par),
+        WattsStrogatzGraph{weight_par, ..} => Some(weight_par),
+    }
+}
+
+fn extract_weight_parameters(data: &GraphBenchmarkData) -> WeightParameters {
+    use GraphBenchmarkData::*;
+    match data {
+        RandomGraph{weight_par, ..} => *weight_par,
+        RealWorldGraph{weight_mode: WeightMode::Generate(weight_par), ..} => *weight_par,
+        RealWorldGraph{..} => WeightParameters{ weight_range: (0u32, 10u32), rng_seed: 10u64, distribution: WeightDist::Uniform },
+        SavedGraph{weight_par, ..} => *weight_par,
+        BipartiteGraph{weight_par, ..} => *weight_par,
+        ErdosRenyiGraph{weight_par, ..} => *weight_par,
+        BarabasiAlbertGraph{weight_par, ..} => *weight_par,
+        GridGraph{weight_par, ..} => *weight_par,
+        RmatGraph{weight_par, ..} => *weight_par,
+        WattsStrogatzGraph{weight_par, ..} => *weight_par,
+    }
+}
+
+/// Errors from parsing the benchmark arguments.
+#[derive(Debug)]
+pub enum ArgError {
+    /// A positional argument or an option value is missing.
+    Missing { argument: &'static str, expected: &'static str },
+    /// An argument could not be parsed as the expected type.
+    Invalid { argument: &'static str, expected: &'static str, value: String },
+    /// The arguments parsed, but their values are not allowed.
+    Constraint(String),
+}
+
+impl std::fmt::Display for ArgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ArgError::Missing { argument, expected } => write!(f, "Missing {}, expected {}", argument, expected),
+            ArgError::Invalid { argument, expected, value } => write!(f, "Invalid {} {:?}, expected {}", argument, value, expected),
+            ArgError::Constraint(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// Positional argument order of every type of graph data, to print along with argument errors.
+pub fn usage() -> String {
+    let common = "<rounds> <per_update> <source> <target> [inspect|validate] [options] [timely arguments]";
+    [
+        format!("Usage: <benchmark> <type of data> ... {}", common),
+        format!("  real <path_to_file> <generate_string> [<low> <high>] {}", common),
+        format!("  random <nodes> <edges> <low> <high> {}", common),
+        format!("  saved <path_to_binary> <low> <high> {}", common),
+        format!("  bipartite <users> <items> <edges> <skew> <low> <high> {}", common),
+        format!("  erdos-renyi <nodes> <p> <low> <high> {}", common),
+        format!("  barabasi <nodes> <m> <low> <high> {}", common),
+        format!("  grid <rows> <cols> <low> <high> {}", common),
+        format!("  rmat <scale> <edge_factor> <a> <b> <c> <d> <low> <high> {}", common),
+        format!("  watts-strogatz <nodes> <k> <beta> <low> <high> {}", common),
+        "<low> and <high> are only passed to real when <generate_string> is \"generate\".".to_string(),
+    ]
+    .join("\n")
+}
+
+/// Common command line argument parsers. Makes sure we parse the same arguments
+/// in all benchmarking executables. Panics with the usage on invalid arguments.
+/// With `validate` in place of the inspect string, prints the parsed description and exits
+/// the process instead of returning it, without running the benchmark.
+pub fn parse_graph_benchmark_arguments(arguments: std::env::Args) -> BenchmarkDescription {
+    let benchmark = try_parse_graph_benchmark_arguments(arguments).unwrap_or_else(|error| panic!("{}\n\n{}", error, usage()));
+    if benchmark.dry_run {
+        println!("{:#?}", benchmark);
+        println!("Arguments are valid");
+        std::process::exit(0);
+    }
+    benchmark
+}
+
+/// Like `parse_graph_benchmark_arguments`, but returns the first missing or invalid argument
+/// as an error. The first argument is the executable name, as in `std::env::args`.
+pub fn try_parse_graph_benchmark_arguments<I: Iterator<Item = String>>(mut arguments: I) -> Result<BenchmarkDescription, ArgError> {
+    next_value::<String, _>(&mut arguments, "executable name", "the name of the benchmark")?;
+
+    let type_of_data: String = next_value(&mut arguments, "type of graph data", "one of: real, random, saved, bipartite, erdos-renyi, barabasi, grid, rmat, watts-strogatz")?;
+    let graph_type = match type_of_data.as_str() {
+        "real" => GraphDataType::RealWorld,
+        "random" => GraphDataType::Random,
+        "saved" => GraphDataType::Saved,
+        "bipartite" => GraphDataType::Bipartite,
+        "erdos-renyi" => GraphDataType::ErdosRenyi,
+        "barabasi" => GraphDataType::BarabasiAlbert,
+        "grid" => GraphDataType::Grid,
+        "rmat" => GraphDataType::Rmat,
+        "watts-strogatz" => GraphDataType::WattsStrogatz,
+        _ => return Err(ArgError::Invalid { argument: "type of graph data", expected: "one of: real, random, saved, bipartite, erdos-renyi, barabasi, grid, rmat, watts-strogatz", value: type_of_data }),
+    };
+
+    let graph_data = match graph_type {
+        GraphDataType::Random => {
+            let nodes: u32 = next_value(&mut arguments, "number of nodes", "a non-negative integer")?;
+            let edges: u32 = next_value(&mut arguments, "number of edges", "a non-negative integer")?;
+            GraphBenchmarkData::RandomGraph {nodes: nodes, edges: edges, connected: false, weight_par: next_weight_parameters(&mut arguments)? }
+        }
+        GraphDataType::RealWorld => {
+            let graph_file: String = next_value(&mut arguments, "path to graph file", "a path")?;
+            let path_to_file = std::path::Path::new(&graph_file);
+            if graph_file != STDIN_FILENAME && !path_to_file.exists() {
+                return Err(ArgError::Constraint(format!("Graph file {:?} does not exist", graph_file)));
+            }
+            let weight_generation: String = next_value(&mut arguments, "weight generation", "\"generate\", \"scale\" or any other string")?;
+            let weight_mode = match weight_generation.as_str() {
+                "generate" => WeightMode::Generate(next_weight_parameters(&mut arguments)?),
+                "scale" => {
+                    let factor: f32 = next_value(&mut arguments, "weight scale factor", "a number")?;
+                    if !(factor >= 0.0 && factor.is_finite()) {
+                        return Err(ArgError::Constraint("Weight scale factor must be a non-negative number".to_string()));
+                    }
+                    WeightMode::ScaleFile(factor)
+                }
+                _ => WeightMode::UseFile,
+            };
+            GraphBenchmarkData::RealWorldGraph { path_to_edge_list: graph_file, weight_mode: weight_mode }
+        }
+        GraphDataType::Saved => {
+            let graph_file: String = next_value(&mut arguments, "path to saved graph file", "a path")?;
+            if graph_file != STDIN_FILENAME && !std::path::Path::new(&graph_file).exists() {
+                return Err(ArgError::Constraint(format!("Saved graph file {:?} does not exist", graph_file)));
+            }
+            GraphBenchmarkData::SavedGraph { path_to_binary: graph_file, weight_par: next_weight_parameters(&mut arguments)? }
+        }
+        GraphDataType::Bipartite => {
+            let users: u32 = next_value(&mut arguments, "number of users", "a non-negative integer")?;
+            let items: u32 = next_value(&mut arguments, "number of items", "a non-negative integer")?;
+            let edges: u32 = next_value(&mut arguments, "number of edges", "a non-negative integer")?;
+            let skew: f64 = next_value(&mut arguments, "skew factor", "a number")?;
+            if users == 0 || items == 0 {
+                return Err(ArgError::Constraint("Bipartite graphs need at least one user and one item".to_string()));
+            }
+            if !(skew >= 0.0) {
+                return Err(ArgError::Constraint("Skew factor must not be negative".to_string()));
+            }
+            GraphBenchmarkData::BipartiteGraph { users: users, items: items, edges: edges, skew: skew, weight_par: next_weight_parameters(&mut arguments)? }
+        }
+        GraphDataType::ErdosRenyi => {
+            let nodes: u32 = next_value(&mut arguments, "number of nodes", "a non-negative integer")?;
+            let p: f64 = next_value(&mut arguments, "edge probability", "a number")?;
+            if !(p >= 0.0 && p <= 1.0) {
+                return Err(ArgError::Constraint("Edge probability must be between 0 and 1".to_string()));
+            }
+            GraphBenchmarkData::ErdosRenyiGraph { nodes: nodes, p: p, weight_par: next_weight_parameters(&mut arguments)? }
+        }
+        GraphDataType::BarabasiAlbert => {
+            let nodes: u32 = next_value(&mut arguments, "number of nodes", "a non-negative integer")?;
+            let m: u32 = next_value(&mut arguments, "number of edges per node", "a non-negative integer")?;
+            if m == 0 || m >= nodes {
+                return Err(ArgError::Constraint("Number of edges per node must be positive and less than the number of nodes".to_string()));
+            }
+            GraphBenchmarkData::BarabasiAlbertGraph { nodes: nodes, m: m, weight_par: next_weight_parameters(&mut arguments)? }
+        }
+        GraphDataType::Grid => {
+            let rows: u32 = next_value(&mut arguments, "number of rows", "a non-negative integer")?;
+            let cols: u32 = next_value(&mut arguments, "number of columns", "a non-negative integer")?;
+            if rows.checked_mul(cols).is_none() {
+                return Err(ArgError::Constraint("Number of grid nodes must fit in a node index".to_string()));
+            }
+            GraphBenchmarkData::GridGraph { rows: rows, cols: cols, weight_par: next_weight_parameters(&mut arguments)? }
+        }
+        GraphDataType::Rmat => {
+            let scale: u32 = next_value(&mut arguments, "scale", "a non-negative integer")?;
+            let edge_factor: u32 = next_value(&mut arguments, "edge factor", "a non-negative integer")?;
+            let a: f64 = next_value(&mut arguments, "probability a", "a number")?;
+            let b: f64 = next_value(&mut arguments, "probability b", "a number")?;
+            let c: f64 = next_value(&mut arguments, "probability c", "a number")?;
+            let d: f64 = next_value(&mut arguments, "probability d", "a number")?;
+            if scale > 31 || (edge_factor as u64) << scale > std::u32::MAX as u64 {
+                return Err(ArgError::Constraint("R-MAT scale must be at most 31, and the number of edges must fit in 32 bits".to_string()));
+            }
+            if !(a >= 0.0 && b >= 0.0 && c >= 0.0 && d >= 0.0 && (a + b + c + d - 1.0).abs() < 1e-6) {
+                return Err(ArgError::Constraint("R-MAT probabilities must not be negative and must sum to 1".to_string()));
+            }
+            GraphBenchmarkData::RmatGraph { scale: scale, edge_factor: edge_factor, probabilities: (a, b, c, d), weight_par: next_weight_parameters(&mut arguments)? }
+        }
+        GraphDataType::WattsStrogatz => {
+            let nodes: u32 = next_value(&mut arguments, "number of nodes", "a non-negative integer")?;
+            let k: u32 = next_value(&mut arguments, "number of neighbours", "an even non-negative integer")?;
+            let beta: f64 = next_value(&mut arguments, "rewiring probability", "a number")?;
+            if k % 2 != 0 || k >= nodes {
+                return Err(ArgError::Constraint("Number of neighbours must be even and less than the number of nodes".to_string()));
+            }
+            if !(beta >= 0.0 && beta <= 1.0) {
+                return Err(ArgError::Constraint("Rewiring probability must be between 0 and 1".to_string()));
+            }
+            GraphBenchmarkData::WattsStrogatzGraph { nodes: nodes, k: k, beta: beta, weight_par: next_weight_parameters(&mut arguments)? }
+        }
+    };
+
+    let num_rounds: u32 = next_value(&mut arguments, "number of rounds", "a non-negative integer")?;
+    let edges_per_update: u32 = next_value(&mut arguments, "number of edges per round", "a non-negative integer")?;
+
+    let sources_text: String = next_value(&mut arguments, "source node", "a node index or a comma separated list of node indices")?;
+    let sources = sources_text
+        .split(',')
+        .map(|source| source.parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|_| ArgError::Invalid { argument: "source node", expected: "a node index or a comma separated list of node indices", value: sources_text.clone() })?;
+    let source = sources[0];
+
+    let target_text: String = next_value(&mut arguments, "target node", "a node index or all")?;
+    let all_targets = target_text == "all";
+    let target: u32 = if all_targets {
+        0
+    } else {
+        target_text
+            .parse()
+            .map_err(|_| ArgError::Invalid { argument: "target node", expected: "a node index or all", value: target_text.clone() })?
+    };
+
+    let search_query = SearchQuery {source: source, sources: sources, target: target, all_targets: all_targets};
+
+    // Everything after the target node is optional: the inspect string followed by
+    // named benchmark options and timely arguments in any order.
+    let optional_arguments: Vec<String> = arguments.collect();
+
+    let inspect = optional_arguments.first().map(|x| x == "inspect").unwrap_or(false);
+    let dry_run = optional_arguments.first().map(|x| x == "validate").unwrap_or(false);
+
+    let trace_output = find_option_value(&optional_arguments, "--trace-output")?;
+    let turn_penalties = find_option_value(&optional_arguments, "--turn-penalties")?;
+    let dense_out = find_option_value(&optional_arguments, "--dense-out")?;
+    let count_records = find_flag(&optional_arguments, "--count-records");
+    let count_reachable = find_flag(&optional_arguments, "--count-reachable");
+    let permute_nodes = find_flag(&optional_arguments, "--permute-nodes");
+    let compare_recompute = find_flag(&optional_arguments, "--compare-recompute");
+    let cpu_time = find_flag(&optional_arguments, "--cpu-time");
+    let memory = find_flag(&optional_arguments, "--memory");
+    let undirected = find_flag(&optional_arguments, "--undirected");
+    let symmetric = undirected || find_flag(&optional_arguments, "--symmetric");
+    let convergence_tail = find_flag(&optional_arguments, "--convergence-tail");
+    let reconstruct_path = find_flag(&optional_arguments, "--reconstruct-path");
+    let check_distances = find_flag(&optional_arguments, "--check-distances");
+    let signed_weights = find_flag(&optional_arguments, "--signed-weights");
+    match &graph_data {
+        GraphBenchmarkData::RealWorldGraph { weight_mode: WeightMode::UseFile, .. } => {}
+        _ if signed_weights => {
+            return Err(ArgError::Constraint("--signed-weights needs a real world graph with the weights of the file".to_string()));
+        }
+        _ => {}
+    }
+    let mut graph_data = graph_data;
+    if find_flag(&optional_arguments, "--connected") {
+        match &mut graph_data {
+            GraphBenchmarkData::RandomGraph { nodes, edges, connected, .. } => {
+                if (*edges as u64) + 1 < *nodes as u64 {
+                    return Err(ArgError::Constraint("--connected needs at least <nodes> - 1 edges for the spanning tree".to_string()));
+                }
+                *connected = true;
+            }
+            _ => return Err(ArgError::Constraint("--connected only applies to random graphs".to_string())),
+        }
+    }
+    let parallel_loading = find_flag(&optional_arguments, "--parallel-loading");
+    if parallel_loading && !supports_partitioned_loading(&graph_data) {
+        return Err(ArgError::Constraint("--parallel-loading supports random graphs without --connected, and saved graphs and real graphs with weights in the file, scaled or not, other than standard input".to_string()));
+    }
+    let dataflow_dot = find_option_value(&optional_arguments, "--dataflow-dot")?;
+    let graph_dot = find_option_value(&optional_arguments, "--graph-dot")?;
+    let node_attributes = find_option_value(&optional_arguments, "--node-attributes")?;
+    let attribute_values: Option<std::collections::HashSet<String>> = find_option_value(&optional_arguments, "--attribute-values")?
+        .map(|x| x.split(',').map(|value| value.to_string()).collect());
+    if node_attributes.is_some() != attribute_values.is_some() {
+        return Err(ArgError::Constraint("--node-attributes and --attribute-values must be passed together".to_string()));
+    }
+    let batch_sizes: Option<Vec<u32>> = match find_option_value(&optional_arguments, "--batch-sizes")? {
+        Some(sizes) => Some(sizes
+            .split(',')
+            .map(|size| size.parse().map_err(|_| ArgError::Invalid { argument: "--batch-sizes", expected: "a comma separated list of integers", value: sizes.clone() }))
+            .collect::<Result<_, _>>()?),
+        None => None,
+    };
+    if batch_sizes.as_ref().map_or(false, |sizes| sizes.contains(&0)) {
+        return Err(ArgError::Constraint("Batch sizes passed to --batch-sizes must be positive".to_string()));
+    }
+    let max_distance: Option<Weight> = parse_option_value(&optional_arguments, "--max-distance", "a non-negative integer")?;
+    let iteration_width: u32 = parse_option_value(&optional_arguments, "--iteration-width", "16 or 32")?.unwrap_or(32);
+    if iteration_width != 16 && iteration_width != 32 {
+        return Err(ArgError::Constraint("Iteration width must be 16 or 32".to_string()));
+    }
+    let iteration_snapshots = find_option_value(&optional_arguments, "--iteration-snapshots")?;
+    let budget: Option<Weight> = parse_option_value(&optional_arguments, "--budget", "a non-negative integer")?;
+    let congestion_factor: Option<f64> = parse_option_value(&optional_arguments, "--congestion-factor", "a number")?;
+    if congestion_factor.map_or(false, |factor| !(factor > 0.0)) {
+        return Err(ArgError::Constraint("Congestion factor must be positive".to_string()));
+    }
+    let spanner_stretch: Option<f64> = parse_option_value(&optional_arguments, "--spanner-stretch", "a number")?;
+    if spanner_stretch.map_or(false, |stretch| !(stretch >= 1.0)) {
+        return Err(ArgError::Constraint("Spanner stretch must be at least 1".to_string()));
+    }
+    let num_paths: usize = parse_option_value(&optional_arguments, "--k", "a positive integer")?
+        .unwrap_or(1);
+    if num_paths == 0 {
+        return Err(ArgError::Constraint("Number of paths passed to --k must be positive".to_string()));
+    }
+    let save_graph = find_option_value(&optional_arguments, "--save-graph")?;
+    let sample_sources: Option<u32> = parse_option_value(&optional_arguments, "--sample-sources", "a non-negative integer")?;
+    let sample_seed: u64 = parse_option_value(&optional_arguments, "--sample-seed", "a non-negative integer")?
+        .unwrap_or(10);
+    let round_changes: Option<usize> = parse_option_value(&optional_arguments, "--round-changes", "a non-negative integer")?;
+    let warmup_rounds: u32 = parse_option_value(&optional_arguments, "--warmup-rounds", "a non-negative integer")?
+        .unwrap_or(0);
+    let rounds_per_batch: u32 = parse_option_value(&optional_arguments, "--rounds-per-batch", "a positive integer")?
+        .unwrap_or(1);
+    if rounds_per_batch == 0 {
+        return Err(ArgError::Constraint("Number of rounds passed to --rounds-per-batch must be positive".to_string()));
+    }
+    let delta: Option<Weight> = parse_option_value(&optional_arguments, "--delta", "a positive integer")?;
+    if delta == Some(0) {
+        return Err(ArgError::Constraint("Bucket width passed to --delta must be positive".to_string()));
+    }
+    let iterations: u32 = parse_option_value(&optional_arguments, "--iterations", "a positive integer")?
+        .unwrap_or(20);
+    if iterations == 0 {
+        return Err(ArgError::Constraint("Number of iterations passed to --iterations must be positive".to_string()));
+    }
+    let start_time: u32 = parse_option_value(&optional_arguments, "--start-time", "a non-negative integer")?
+        .unwrap_or(0);
+    let percentile: f64 = parse_option_value(&optional_arguments, "--percentile", "a number")?
+        .unwrap_or(90.0);
+    if !(percentile > 0.0 && percentile <= 100.0) {
+        return Err(ArgError::Constraint("Percentile must be greater than 0 and at most 100".to_string()));
+    }
+
+    let deletion_ratio: f32 = parse_option_value(&optional_arguments, "--deletion-ratio", "a number")?
+        .unwrap_or(0.0);
+    if !(deletion_ratio >= 0.0 && deletion_ratio <= 1.0) {
+        return Err(ArgError::Constraint("Deletion ratio must be between 0 and 1".to_string()));
+    }
+
+    let seed: u64 = parse_option_value(&optional_arguments, "--seed", "a non-negative integer")?
+        .unwrap_or(10);
+    let weight_distribution: Option<WeightDist> = parse_option_value(&optional_arguments, "--weight-distribution", "uniform, normal:<mean>,<std> or zipf:<exponent>")?;
+    match weight_parameters_mut(&mut graph_data) {
+        Some(weight_par) => {
+            weight_par.rng_seed = seed;
+            if let Some(distribution) = weight_distribution {
+                weight_par.distribution = distribution;
+            }
+        }
+        None if weight_distribution.is_some() => {
+            return Err(ArgError::Constraint("--weight-distribution needs generated weights".to_string()));
+        }
+        None => {}
+    }
+
+    let mut weight_par = extract_weight_parameters(&graph_data);
+    weight_par.rng_seed = seed;
+    let adversarial_updates = find_flag(&optional_arguments, "--adversarial-updates");
+    if adversarial_updates && deletion_ratio > 0.0 {
+        return Err(ArgError::Constraint("Adversarial updates only insert edges, so they take no --deletion-ratio".to_string()));
+    }
+    let graph_updates = if adversarial_updates {
+        GraphBenchmarkUpdates::AdversarialUpdates{edges_per_update: edges_per_update, weight_par: weight_par }
+    } else {
+        GraphBenchmarkUpdates::RandomUpdates{edges_per_update: edges_per_update, weight_par: weight_par, deletion_ratio: deletion_ratio }
+    };
+
+    Ok(BenchmarkDescription {
+        graph_data: graph_data,
+        graph_updates: graph_updates,
+        num_rounds: num_rounds,
+        warmup_rounds: warmup_rounds,
+        search_query: search_query,
+        seed: seed,
+        inspect_results: inspect,
+        dry_run: dry_run,
+        trace_output: trace_output,
+        turn_penalties: turn_penalties,
+        dense_out: dense_out,
+        count_records: count_records,
+        count_reachable: count_reachable,
+        permute_nodes: permute_nodes,
+        compare_recompute: compare_recompute,
+        cpu_time: cpu_time,
+        memory: memory,
+        symmetric: symmetric,
+        directed: !undirected,
+        signed_weights: signed_weights,
+        convergence_tail: convergence_tail,
+        reconstruct_path: reconstruct_path,
+        check_distances: check_distances,
+        parallel_loading: parallel_loading,
+        congestion_factor: congestion_factor,
+        dataflow_dot: dataflow_dot,
+        graph_dot: graph_dot,
+        budget: budget,
+        iteration_snapshots: iteration_snapshots,
+        max_distance: max_distance,
+        iteration_width: iteration_width,
+        batch_sizes: batch_sizes,
+        node_attributes: node_attributes,
+        attribute_values: attribute_values,
+        spanner_stretch: spanner_stretch,
+        num_paths: num_paths,
+        save_graph: save_graph,
+        sample_sources: sample_sources,
+        sample_seed: sample_seed,
+        percentile: percentile,
+        start_time: start_time,
+        iterations: iterations,
+        delta: delta,
+        round_changes: round_changes,
+        rounds_per_batch: rounds_per_batch,
+    })
+}
+
+/// Parse the next positional argument.
+fn next_value<T: std::str::FromStr, I: Iterator<Item = String>>(arguments: &mut I, argument: &'static str, expected: &'static str) -> Result<T, ArgError> {
+    let value = arguments.next().ok_or(ArgError::Missing { argument: argument, expected: expected })?;
+    value.parse().map_err(|_| ArgError::Invalid { argument: argument, expected: expected, value: value })
+}
+
+/// Parse the `<low> <high>` weight range arguments.
+fn next_weight_parameters<I: Iterator<Item = String>>(arguments: &mut I) -> Result<WeightParameters, ArgError> {
+    let lower_weight: u32 = next_value(arguments, "weight lower bound", "a non-negative integer")?;
+    let upper_weight: u32 = next_value(arguments, "weight upper bound", "a non-negative integer")?;
+    if lower_weight > upper_weight {
+        return Err(ArgError::Constraint("Lower weight range must not be greater than upper weight range".to_string()));
+    }
+    Ok(WeightParameters{ weight_range: (lower_weight, upper_weight), rng_seed: 10, distribution: WeightDist::Uniform })
+}
+
+/// Named benchmark options, with whether each is followed by exactly one value or is a flag.
+/// Timely rejects options it does not know, so these are filtered out by `timely_arguments`.
+/// The parser only looks up options listed here, so the two cannot disagree.
+const BENCHMARK_OPTIONS: &[(&str, bool)] = &[
+    ("--trace-output", true),
+    ("--turn-penalties", true),
+    ("--dense-out", true),
+    ("--k", true),
+    ("--save-graph", true),
+    ("--sample-sources", true),
+    ("--sample-seed", true),
+    ("--spanner-stretch", true),
+    ("--congestion-factor", true),
+    ("--dataflow-dot", true),
+    ("--budget", true),
+    ("--iteration-snapshots", true),
+    ("--max-distance", true),
+    ("--iteration-width", true),
+    ("--batch-sizes", true),
+    ("--node-attributes", true),
+    ("--attribute-values", true),
+    ("--percentile", true),
+    ("--start-time", true),
+    ("--round-changes", true),
+    ("--warmup-rounds", true),
+    ("--deletion-ratio", true),
+    ("--weight-distribution", true),
+    ("--seed", true),
+    ("--iterations", true),
+    ("--graph-dot", true),
+    ("--delta", true),
+    ("--rounds-per-batch", true),
+    ("--count-records", false),
+    ("--count-reachable", false),
+    ("--adversarial-updates", false),
+    ("--permute-nodes", false),
+    ("--compare-recompute", false),
+    ("--cpu-time", false),
+    ("--convergence-tail", false),
+    ("--reconstruct-path", false),
+    ("--check-distances", false),
+    ("--parallel-loading", false),
+    ("--memory", false),
+    ("--connected", false),
+    ("--symmetric", false),
+    ("--undirected", false),
+    ("--signed-weights", false),
+];
+
+/// Whether the named option `name` takes a value. Panics if it is not in `BENCHMARK_OPTIONS`.
+fn takes_value(name: &str) -> bool {
+    match BENCHMARK_OPTIONS.iter().find(|(option, _)| *option == name) {
+        Some((_, takes_value)) => *takes_value,
+        None => panic!("{} is missing from BENCHMARK_OPTIONS", name),
+    }
+}
+
+/// Whether the flag `name` is given.
+fn find_flag(arguments: &[String], name: &'static str) -> bool {
+    assert!(!takes_value(name), "{} takes a value", name);
+    arguments.iter().any(|x| x == name)
+}
+
+fn find_option_value(arguments: &[String], name: &'static str) -> Result<Option<String>, ArgError> {
+    assert!(takes_value(name), "{} is a flag", name);
+    match arguments.iter().position(|x| x == name) {
+        Some(position) => match arguments.get(position + 1) {
+            Some(value) => Ok(Some(value.clone())),
+            None => Err(ArgError::Missing { argument: name, expected: "a value" }),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Parse the value of a named option, if it is given.
+fn parse_option_value<T: std::str::FromStr>(arguments: &[String], name: &'static str, expected: &'static str) -> Result<Option<T>, ArgError> {
+    match find_option_value(arguments, name)? {
+        Some(value) => value.parse().map(Some).map_err(|_| ArgError::Invalid { argument: name, expected: expected, value: value }),
+        None => Ok(None),
+    }
+}
+
+/// Command line arguments with the named benchmark options removed, to be passed to timely.
+pub fn timely_arguments(arguments: std::env::Args) -> impl Iterator<Item = String> {
+    without_benchmark_options(arguments).into_iter()
+}
+
+/// The arguments without the named benchmark options and their values.
+fn without_benchmark_options<I: Iterator<Item = String>>(mut arguments: I) -> Vec<String> {
+    let mut filtered = Vec::new();
+    while let Some(argument) = arguments.next() {
+        match BENCHMARK_OPTIONS.iter().find(|(option, _)| *option == argument) {
+            Some((_, true)) => {
+                arguments.next();
+            }
+            Some((_, false)) => (),
+            None => filtered.push(argument),
+        }
+    }
+    filtered
+}
+
+/// Count the cross-partition edges leaving each of `parts` partitions when nodes are
+/// hash partitioned as `node % parts`. The sum is the total edge cut, and the spread
+/// across partitions shows how evenly communication is split between workers.
+pub fn partition_edge_cut(edges: &[WeightedEdge], parts: usize) -> Vec<usize> {
+    if parts == 0 {
+        panic!("Number of partitions must be positive");
+    }
+    let mut cut = vec![0; parts];
+    for (from, to, _) in edges.iter() {
+        let from_part = *from as usize % parts;
+        if from_part != *to as usize % parts {
+            cut[from_part] += 1;
+        }
+    }
+    cut
+}
+
+/// The edges with both endpoints in `nodes`.
+pub fn induced_subgraph(edges: Vec<WeightedEdge>, nodes: &std::collections::HashSet<Node>) -> Vec<WeightedEdge> {
+    edges
+        .into_iter()
+        .filter(|(from, to, _)| nodes.contains(from) && nodes.contains(to))
+        .collect()
+}
+
+/// The subgraph induced by the nodes whose attribute is one of `values`.
+/// Nodes without an attribute are left out.
+pub fn induced_subgraph_by_attribute(
+    edges: Vec<WeightedEdge>,
+    attributes: &std::collections::HashMap<Node, String>,
+    values: &std::collections::HashSet<String>,
+) -> Vec<WeightedEdge> {
+    let nodes = attributes
+        .iter()
+        .filter(|(_, attribute)| values.contains(*attribute))
+        .map(|(node, _)| *node)
+        .collect();
+    induced_subgraph(edges, &nodes)
+}
+
+/// Degree assortativity: the Pearson correlation between the out-degree of the source and
+/// the in-degree of the target across all edges. Positive values mean high-degree nodes tend
+/// to connect to each other. Returns `None` if there are no edges or either degree is constant.
+pub fn degree_assortativity(edges: &[WeightedEdge]) -> Option<f64> {
+    use std::collections::HashMap;
+
+    let mut out_degree: HashMap<Node, f64> = HashMap::new();
+    let mut in_degree: HashMap<Node, f64> = HashMap::new();
+    for (from, to, _) in edges.iter() {
+        *out_degree.entry(*from).or_insert(0.0) += 1.0;
+        *in_degree.entry(*to).or_insert(0.0) += 1.0;
+    }
+    let count = edges.len() as f64;
+    let (mut sum_x, mut sum_y, mut sum_xx, mut sum_yy, mut sum_xy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (from, to, _) in edges.iter() {
+        let x = out_degree[from];
+        let y = in_degree[to];
+        sum_x += x;
+        sum_y += y;
+        sum_xx += x * x;
+        sum_yy += y * y;
+        sum_xy += x * y;
+    }
+    let covariance = sum_xy / count - (sum_x / count) * (sum_y / count);
+    let variance_x = sum_xx / count - (sum_x / count).powi(2);
+    let variance_y = sum_yy / count - (sum_y / count).powi(2);
+    if edges.is_empty() || variance_x <= 0.0 || variance_y <= 0.0 {
+        return None;
+    }
+    Some(covariance / (variance_x * variance_y).sqrt())
+}
+
+/// Counts of the weights in `buckets` equally wide buckets over the range of the weights,
+/// as pairs of the lowest weight of each bucket and its count. There are fewer buckets if the
+/// range has fewer weights, and none if there are no edges. Takes two passes over the edges,
+/// one for both ends of the range and one for the counts.
+pub fn weight_histogram(edges: &[WeightedEdge], buckets: usize) -> Vec<(u32, usize)> {
+    if buckets == 0 {
+        panic!("Number of buckets must be positive");
+    }
+    let range = edges.iter().fold(None, |range, (_, _, weight)| match range {
+        None => Some((*weight, *weight)),
+        Some((low, high)) => Some((std::cmp::min(low, *weight), std::cmp::max(high, *weight))),
+    });
+    let (low, high) = match range {
+        Some(range) => range,
+        None => return Vec::new(),
+    };
+    // Wide enough for the buckets to cover the range, computed in u64 as it can hold every u32.
+    let range = (high - low) as u64 + 1;
+    let width = (range + buckets as u64 - 1) / buckets as u64;
+    let num_buckets = ((range + width - 1) / width) as usize;
+    let mut histogram: Vec<(u32, usize)> = (0..num_buckets).map(|bucket| (low + (bucket as u64 * width) as u32, 0)).collect();
+    for (_, _, weight) in edges.iter() {
+        histogram[((*weight - low) as u64 / width) as usize].1 += 1;
+    }
+    histogram
+}
+
+/// Structural statistics of an edge list, for a quick look at a graph before benchmarking.
+/// Nodes are indexed from 0 up to the largest index in the list, as the generator assumes,
+/// so indices without any edge count as isolated nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GraphStats {
+    pub nodes: u32,
+    pub edges: usize,
+    pub average_out_degree: f64,
+    pub max_out_degree: usize,
+    pub self_loops: usize,
+    /// Nodes without incoming or outgoing edges.
+    pub isolated_nodes: u32,
+}
+
+impl std::fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "nodes {} edges {} out-degree average {:.2} max {} self loops {} isolated nodes {}",
+            self.nodes, self.edges, self.average_out_degree, self.max_out_degree, self.self_loops, self.isolated_nodes
+        )
+    }
+}
+
+/// Computes the `GraphStats` of the edges, in two passes over them.
+pub fn graph_stats(edges: &[WeightedEdge]) -> GraphStats {
+    let nodes = num_nodes_from_edge_list(edges);
+    let mut out_degree = vec![0usize; nodes as usize];
+    let mut connected = vec![false; nodes as usize];
+    let mut self_loops = 0;
+    for (from, to, _) in edges.iter() {
+        out_degree[*from as usize] += 1;
+        connected[*from as usize] = true;
+        connected[*to as usize] = true;
+        if from == to {
+            self_loops += 1;
+        }
+    }
+    GraphStats {
+        nodes: nodes,
+        edges: edges.len(),
+        average_out_degree: if nodes == 0 { 0.0 } else { edges.len() as f64 / nodes as f64 },
+        max_out_degree: out_degree.iter().cloned().max().unwrap_or(0),
+        self_loops: self_loops,
+        isolated_nodes: connected.iter().filter(|connected| !**connected).count() as u32,
+    }
+}
+
+/// Write edges in a binary format: the number of edges as a little-endian `u64`,
+/// followed by the source, target and weight of each edge as little-endian `u32` values.
+pub fn write_binary_graph(edges: &[WeightedEdge], path: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    file.write_all(&(edges.len() as u64).to_le_bytes())?;
+    for (from, to, weight) in edges.iter() {
+        file.write_all(&from.to_le_bytes())?;
+        file.write_all(&to.to_le_bytes())?;
+        file.write_all(&weight.to_le_bytes())?;
+    }
+    file.flush()
+}
+
+/// Write edges as lines of whitespace separated `from to weight`, the text format read by
+/// `GraphLoader::load_weighted_graph`, which reads them back in the same order.
+pub fn write_weighted_graph(edges: &[WeightedEdge], path: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    for (from, to, weight) in edges.iter() {
+        writeln!(file, "{} {} {}", from, to, weight)?;
+    }
+    file.flush()
+}
+
+/// Write edges as lines of whitespace separated `from to`, the text format read by
+/// `GraphLoader::load_unweighted_graph`, which reads them back in the same order.
+pub fn write_unweighted_graph(edges: &[UnweightedEdge], path: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    for (from, to) in edges.iter() {
+        writeln!(file, "{} {}", from, to)?;
+    }
+    file.flush()
+}
+
+/// Distance written for nodes that cannot be reached from the source.
+pub const UNREACHABLE: Weight = u32::max_value();
+
+/// Write distances as a dense array of little-endian `u32` values indexed by node ID,
+/// with `UNREACHABLE` for nodes that have no distance.
+/// The file can be read with `numpy.fromfile(path, dtype='<u4')`.
+pub fn write_dense_distances<I>(path: &str, num_nodes: u32, distances: I) -> std::io::Result<()>
+where
+    I: IntoIterator<Item = (Node, Weight)>,
+{
+    use std::io::Write;
+
+    let mut dense = vec![UNREACHABLE; num_nodes as usize];
+    for (node, distance) in distances {
+        if node as usize >= dense.len() {
+            dense.resize(node as usize + 1, UNREACHABLE);
+        }
+        dense[node as usize] = distance;
+    }
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    for distance in dense {
+        file.write_all(&distance.to_le_bytes())?;
+    }
+    file.flush()
+}
+
+/// A graph in the DOT format of Graphviz, with the weights as edge labels. The nodes of
+/// `path_to_highlight` and the edges between consecutive ones are drawn in red. Only
+/// practical for small graphs, for example to inspect a surprising shortest path.
+pub fn to_dot(edges: &[WeightedEdge], path_to_highlight: Option<&[Node]>) -> String {
+    use std::collections::{BTreeSet, HashSet};
+
+    let path = path_to_highlight.unwrap_or(&[]);
+    let path_nodes: HashSet<Node> = path.iter().cloned().collect();
+    let path_edges: HashSet<(Node, Node)> = path.windows(2).map(|pair| (pair[0], pair[1])).collect();
+    let mut nodes: BTreeSet<Node> = path_nodes.iter().cloned().collect();
+    for (from, to, _) in edges.iter() {
+        nodes.insert(*from);
+        nodes.insert(*to);
+    }
+
+    let mut dot = String::from("digraph G {\n");
+    for node in nodes.iter() {
+        if path_nodes.contains(node) {
+            dot.push_str(&format!("    {} [color=red, fontcolor=red];\n", node));
+        } else {
+            dot.push_str(&format!("    {};\n", node));
+        }
+    }
+    for (from, to, weight) in edges.iter() {
+        if path_edges.contains(&(*from, *to)) {
+            dot.push_str(&format!("    {} -> {} [label=\"{}\", color=red, fontcolor=red];\n", from, to, weight));
+        } else {
+            dot.push_str(&format!("    {} -> {} [label=\"{}\"];\n", from, to, weight));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Write a dataflow graph in the DOT format of Graphviz. Operators are identified by their
+/// address in the scope hierarchy, and each channel connects the addresses of its endpoints.
+/// Operators containing a scope are drawn as a cluster around the operators inside it.
+pub fn write_dataflow_dot(path: &str, operators: &[(Vec<usize>, String)], channels: &[(Vec<usize>, Vec<usize>)]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    fn node_id(addr: &[usize]) -> String {
+        let parts: Vec<String> = addr.iter().map(|index| index.to_string()).collect();
+        format!("op_{}", parts.join("_"))
+    }
+
+    fn write_scope<W: Write>(file: &mut W, operators: &[(Vec<usize>, String)], scope: &[usize], depth: usize) -> std::io::Result<()> {
+        let indent = "    ".repeat(depth);
+        for (addr, name) in operators.iter() {
+            if addr.len() != scope.len() + 1 || !addr.starts_with(scope) {
+                continue;
+            }
+            let label = format!("{} {:?}", escape_json(name), addr);
+            if operators.iter().any(|(inner, _)| inner.len() > addr.len() && inner.starts_with(addr)) {
+                writeln!(file, "{}subgraph cluster_{} {{", indent, node_id(addr))?;
+                writeln!(file, "{}    label=\"{}\";", indent, label)?;
+                writeln!(file, "{}    {} [label=\"{}\", shape=diamond];", indent, node_id(addr), label)?;
+                write_scope(file, operators, addr, depth + 1)?;
+                writeln!(file, "{}}}", indent)?;
+            } else {
+                writeln!(file, "{}{} [label=\"{}\"];", indent, node_id(addr), label)?;
+            }
+        }
+        Ok(())
+    }
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writeln!(file, "digraph dataflow {{")?;
+    writeln!(file, "    node [shape=box];")?;
+    write_scope(&mut file, operators, &[], 1)?;
+    for (source, target) in channels.iter() {
+        writeln!(file, "    {} -> {};", node_id(source), node_id(target))?;
+    }
+    writeln!(file, "}}")?;
+    file.flush()
+}
+
+/// Errors from generating graph data, returned by the `try_` methods of `GraphDataGenerator`
+/// in place of the panics of the others.
+#[derive(Debug)]
+pub enum GenError {
+    /// Updates were requested before the initial graph, or after an initial graph without nodes.
+    NotInitialized,
+    /// The real-world or saved graph file has no edges.
+    EmptyGraph,
+    /// Adversarial updates were requested without nodes near the source.
+    NoNearNodes,
+    /// Deletions were requested from a generator without history.
+    NoHistory,
+    /// Another worker could not generate or load its share of the initial graph.
+    OtherWorkerFailed,
+    Load(GraphLoadError),
+    /// The graph cache could not be read.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for GenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GenError::NotInitialized => write!(f, "Graph updates generated before the initial graph"),
+            GenError::EmptyGraph => write!(f, "Graph file has no edges"),
+            GenError::NoNearNodes => write!(f, "Adversarial updates need the nodes closest to the source, which this benchmark does not provide"),
+            GenError::NoHistory => write!(f, "Deletions need a generator with history"),
+            GenError::OtherWorkerFailed => write!(f, "Another worker could not generate or load its share of the initial graph"),
+            GenError::Load(error) => write!(f, "{}", error),
+            GenError::Io(error) => write!(f, "I/O error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for GenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GenError::Load(error) => Some(error),
+            GenError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<GraphLoadError> for GenError {
+    fn from(error: GraphLoadError) -> GenError {
+        GenError::Load(error)
+    }
+}
+
+impl From<std::io::Error> for GenError {
+    fn from(error: std::io::Error) -> GenError {
+        GenError::Io(error)
+    }
+}
+
+pub struct GraphDataGenerator {
+    rng: rand_chacha::ChaCha8Rng,
+    num_nodes: u32, 
+    seed: u64,
+    // Edges currently in the graph, the initial ones and those inserted since, minus deletions.
+    // Only kept with history.
+    present_edges: Option<Vec<WeightedEdge>>,
+    // Initial edges and inserted update edges, minus deleted ones.
+    total_edges: usize,
+    // Inserted and deleted update edges.
+    update_edges: usize,
+    symmetric: bool,
+}
+
+/// Number of edges of a random initial graph drawn from the same random stream when the
+/// workers generate it in parallel.
+const PARTITION_CHUNK_EDGES: u32 = 1 << 16;
+
+/// Environment variable naming a directory in which generated graphs are cached.
+pub const GRAPH_CACHE_ENV: &str = "TIMELY_PATH_GRAPH_CACHE";
+
+/// File name identifying a generated graph by its generation parameters and seed,
+/// or `None` for graphs that are loaded from files.
+fn graph_cache_key(desc: &GraphBenchmarkData, seed: u64) -> Option<String> {
+    use GraphBenchmarkData::*;
+    let key = match desc {
+        RandomGraph { nodes, edges, connected, weight_par } => {
+            let connected = if *connected { "-connected" } else { "" };
+            format!("random-{}-{}{}", nodes, edges, connected) + &weight_key(weight_par)
+        }
+        BipartiteGraph { users, items, edges, skew, weight_par } => {
+            format!("bipartite-{}-{}-{}-{}", users, items, edges, skew) + &weight_key(weight_par)
+        }
+        ErdosRenyiGraph { nodes, p, weight_par } => {
+            format!("erdos-renyi-{}-{}", nodes, p) + &weight_key(weight_par)
+        }
+        BarabasiAlbertGraph { nodes, m, weight_par } => {
+            format!("barabasi-{}-{}", nodes, m) + &weight_key(weight_par)
+        }
+        GridGraph { rows, cols, weight_par } => {
+            format!("grid-{}-{}", rows, cols) + &weight_key(weight_par)
+        }
+        RmatGraph { scale, edge_factor, probabilities: (a, b, c, d), weight_par } => {
+            format!("rmat-{}-{}-{}-{}-{}-{}", scale, edge_factor, a, b, c, d) + &weight_key(weight_par)
+        }
+        WattsStrogatzGraph { nodes, k, beta, weight_par } => {
+            format!("watts-strogatz-{}-{}-{}", nodes, k, beta) + &weight_key(weight_par)
+        }
+        RealWorldGraph { .. } | SavedGraph { .. } => return None,
+    };
+    Some(format!("{}-seed{}.bin", key, seed))
+}
+
+/// The path of a cache file as the file loaders take it, which must be valid UTF-8.
+fn cache_path_str(path: &std::path::Path) -> std::io::Result<&str> {
+    path.to_str().ok_or_else(|| {
+        let message = format!("Cache path {:?} is not valid UTF-8", path);
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+    })
+}
+
+fn weight_key(weight_par: &WeightParameters) -> String {
+    let distribution = match weight_par.distribution {
+        WeightDist::Uniform => String::new(),
+        WeightDist::Normal { mean, std } => format!("-normal{}-{}", mean, std),
+        WeightDist::Zipf { exponent } => format!("-zipf{}", exponent),
+    };
+    format!("-w{}-{}", weight_par.weight_range.0, weight_par.weight_range.1) + &distribution
+}
+
+/// Number of nodes assuming they are indexed from 0, the largest index in the list plus one.
+fn num_nodes_from_edge_list(edges: &[WeightedEdge]) -> u32 {
+    let mut num_nodes = 0;
+    for (from, to, ..) in edges.iter() {
+        num_nodes = std::cmp::max(num_nodes, *from + 1);
+        num_nodes = std::cmp::max(num_nodes, *to + 1);
+    }
+    num_nodes
+}
+
+impl GraphDataGenerator {
+
+    pub fn new_from_seed(seed: u64) -> GraphDataGenerator {
+        GraphDataGenerator { rng: default_rng(seed), num_nodes: 0, seed: seed, present_edges: None, total_edges: 0, update_edges: 0, symmetric: false }
+    }
+
+    /// Keep the history of the edges in the graph, which `gen_graph_changes_near` needs to
+    /// delete edges. The initial graph is kept, unless replaced with `set_present_edges`.
+    pub fn with_history(mut self, enabled: bool) -> GraphDataGenerator {
+        self.present_edges = if enabled { Some(Vec::new()) } else { None };
+        self
+    }
+
+    /// Emit every edge of the initial graph and the updates together with its reverse, so the
+    /// graph stays symmetric, see `symmetrize_edges`. Deletions then remove both directions, which
+    /// takes a search through the tracked edges for each deletion.
+    pub fn with_symmetric(mut self, enabled: bool) -> GraphDataGenerator {
+        self.symmetric = enabled;
+        self
+    }
+
+    /// Replace the tracked edges, for benchmarks that change the initial graph before using it.
+    pub fn set_present_edges(&mut self, edges: Vec<WeightedEdge>) {
+        match &mut self.present_edges {
+            Some(present_edges) => *present_edges = edges,
+            None => panic!("set_present_edges called on a generator without history"),
+        }
+    }
+
+    /// Replace the number of edges reported by `total_edges_emitted`, for benchmarks that
+    /// change the initial graph before using it.
+    pub fn set_total_edges(&mut self, total_edges: usize) {
+        self.total_edges = total_edges;
+    }
+
+    /// A uniformly random edge currently in the graph, or `None` if it has no edges.
+    pub fn sample_existing_edge(&mut self) -> Option<WeightedEdge> {
+        use rand::distributions::{Distribution, Uniform};
+
+        let present_edges = self.present_edges.as_ref().expect("sample_existing_edge called on a generator without history");
+        if present_edges.is_empty() {
+            return None;
+        }
+        Some(present_edges[Uniform::new(0, present_edges.len()).sample(&mut self.rng)])
+    }
+
+    /// Generate or load the initial graph. If the `TIMELY_PATH_GRAPH_CACHE` environment variable
+    /// names a directory, generated graphs are saved there in the binary format and reused by
+    /// later runs with the same parameters and seed. The generator state is saved alongside,
+    /// so the updates that follow are the same as without the cache.
+    pub fn gen_initial_graph(& mut self, desc: &GraphBenchmarkData) -> Vec<WeightedEdge> {
+        let edges = self.gen_initial_graph_cached(desc).unwrap_or_else(|error| panic!("{}", error));
+        self.set_initial_edges(edges)
+    }
+
+    /// As `gen_initial_graph`, but returns an error if a graph file cannot be loaded or has no edges.
+    pub fn try_gen_initial_graph(& mut self, desc: &GraphBenchmarkData) -> Result<Vec<WeightedEdge>, GenError> {
+        let edges = self.gen_initial_graph_cached(desc)?;
+        match desc {
+            GraphBenchmarkData::RealWorldGraph { .. } | GraphBenchmarkData::SavedGraph { .. } if edges.is_empty() => {
+                Err(GenError::EmptyGraph)
+            }
+            _ => Ok(self.set_initial_edges(edges)),
+        }
+    }
+
+    fn set_initial_edges(&mut self, mut edges: Vec<WeightedEdge>) -> Vec<WeightedEdge> {
+        if self.symmetric {
+            edges = symmetrize_edges(edges);
+        }
+        self.total_edges = edges.len();
+        if let Some(present_edges) = &mut self.present_edges {
+            *present_edges = edges.clone();
+        }
+        edges
+    }
+
+    fn gen_initial_graph_cached(& mut self, desc: &GraphBenchmarkData) -> Result<Vec<WeightedEdge>, GenError> {
+        let cache_path = std::env::var(GRAPH_CACHE_ENV)
+            .ok()
+            .and_then(|dir| graph_cache_key(desc, self.seed).map(|key| std::path::Path::new(&dir).join(key)));
+        let cache_path = match cache_path {
+            Some(path) => path,
+            None => return self.gen_initial_graph_uncached(desc),
+        };
+        let state_path = cache_path.with_extension("state");
+        if cache_path.exists() && state_path.exists() {
+            let state = std::fs::read(&state_path)?;
+            if state.len() != 20 {
+                let message = format!("Cached generator state {:?} is corrupt", state_path);
+                return Err(GenError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message)));
+            }
+            let mut word_pos = [0u8; 16];
+            word_pos.copy_from_slice(&state[0..16]);
+            self.rng.set_word_pos(u128::from_le_bytes(word_pos));
+            self.num_nodes = u32::from_le_bytes([state[16], state[17], state[18], state[19]]);
+            let edges = GraphLoader::default().read_binary_graph(cache_path_str(&cache_path)?).map_err(GraphLoadError::from)?;
+            return Ok(edges);
+        }
+
+        let edges = self.gen_initial_graph_uncached(desc)?;
+        // Written to temporary files first, so concurrent runs never read a partial cache.
+        let write_cache = || -> std::io::Result<()> {
+            let temporary_graph = cache_path.with_extension(format!("bin.{}", std::process::id()));
+            let temporary_state = cache_path.with_extension(format!("state.{}", std::process::id()));
+            write_binary_graph(&edges, cache_path_str(&temporary_graph)?)?;
+            let mut state = self.rng.get_word_pos().to_le_bytes().to_vec();
+            state.extend_from_slice(&self.num_nodes.to_le_bytes());
+            std::fs::write(&temporary_state, state)?;
+            std::fs::rename(&temporary_graph, &cache_path)?;
+            std::fs::rename(&temporary_state, &state_path)
+        };
+        if let Err(error) = write_cache() {
+            eprintln!("Could not cache the generated graph in {:?}: {}", cache_path, error);
+        }
+        Ok(edges)
+    }
+
+    fn gen_initial_graph_uncached(& mut self, desc: &GraphBenchmarkData) -> Result<Vec<WeightedEdge>, GenError> {
+        use GraphBenchmarkData::*;
+        let edges = match desc {
+            RandomGraph {nodes, edges, connected: false, weight_par} => {
+                // Update the number of nodes
+                self.num_nodes = *nodes;
+                // Drawn from the generator's own stream, so the graph of a seed stays the same as in
+                // earlier versions. `gen_initial_graph_partitioned` draws a different graph.
+                generate_weighted_graph(&mut self.rng, *nodes, *edges, weight_par)
+            }
+            RandomGraph {nodes, edges, connected: true, weight_par} => {
+                self.num_nodes = *nodes;
+                let edges = generate_connected_graph(&mut self.rng, *nodes, *edges);
+                generate_weights_for_graph(&mut self.rng, edges, weight_par)
+            }
+            RealWorldGraph { path_to_edge_list, weight_mode } => {
+                let loader = GraphLoader::default();
+                let (edges, isolated) = match weight_mode {
+                    WeightMode::UseFile => loader.load_weighted_graph_with_nodes(&path_to_edge_list)?,
+                    WeightMode::Generate(par) => {
+                        let (edges, isolated) = loader.load_unweighted_graph_with_nodes(&path_to_edge_list)?;
+                        (generate_weights_for_graph(&mut self.rng, edges, par), isolated)
+                    }
+                    WeightMode::ScaleFile(factor) => {
+                        let (edges, isolated) = loader.load_weighted_graph_with_nodes(&path_to_edge_list)?;
+                        (scale_weights(edges, *factor), isolated)
+                    }
+                };
+                // Isolated nodes have no edges, but updates can still connect them.
+                self.num_nodes = num_nodes_from_edge_list(&edges);
+                for node in isolated.into_iter() {
+                    self.num_nodes = std::cmp::max(self.num_nodes, node + 1);
+                }
+                edges
+            }
+            SavedGraph { path_to_binary, .. } => {
+                let edges = GraphLoader::default().read_binary_graph(&path_to_binary).map_err(GraphLoadError::from)?;
+                self.num_nodes = num_nodes_from_edge_list(&edges);
+                edges
+            }
+            BipartiteGraph { users, items, edges, skew, weight_par } => {
+                self.num_nodes = *users + *items;
+                generate_bipartite_graph(&mut self.rng, *users, *items, *edges, *skew, weight_par)
+            }
+            ErdosRenyiGraph { nodes, p, weight_par } => {
+                self.num_nodes = *nodes;
+                let edges = generate_erdos_renyi(&mut self.rng, *nodes, *p);
+                generate_weights_for_graph(&mut self.rng, edges, weight_par)
+            }
+            BarabasiAlbertGraph { nodes, m, weight_par } => {
+                self.num_nodes = *nodes;
+                let edges = generate_barabasi_albert(&mut self.rng, *nodes, *m);
+                generate_weights_for_graph(&mut self.rng, edges, weight_par)
+            }
+            GridGraph { rows, cols, weight_par } => {
+                self.num_nodes = *rows * *cols;
+                let edges = generate_grid_graph(*rows, *cols);
+                generate_weights_for_graph(&mut self.rng, edges, weight_par)
+            }
+            RmatGraph { scale, edge_factor, probabilities, weight_par } => {
+                self.num_nodes = 1 << *scale;
+                let edges = generate_rmat(&mut self.rng, *scale, *edge_factor, *probabilities);
+                generate_weights_for_graph(&mut self.rng, edges, weight_par)
+            }
+            WattsStrogatzGraph { nodes, k, beta, weight_par } => {
+                self.num_nodes = *nodes;
+                let edges = generate_watts_strogatz(&mut self.rng, *nodes, *k, *beta);
+                generate_weights_for_graph(&mut self.rng, edges, weight_par)
+            }
+        };
+        Ok(edges)
+    }
+    
+    pub fn max_num_nodes(&self) -> u32 {
+        self.num_nodes
+    }
+
+    /// Replace the number of nodes, which updates are drawn from, for example with the
+    /// largest over the workers after `gen_initial_graph_partitioned`.
+    pub fn set_max_num_nodes(&mut self, num_nodes: u32) {
+        self.num_nodes = num_nodes;
+    }
+
+    /// Generate or load the share of the initial graph of worker `index` out of `peers`. The union
+    /// of the shares is the same for any number of workers: random graphs are drawn in chunks
+    /// of edges, each from its own stream of the seed, which the workers divide between them,
+    /// and files are split between the workers as by `GraphLoader`. The random graph differs
+    /// from the one of `gen_initial_graph`, which draws every edge from the generator's own
+    /// stream, and neither the cache nor the history is used.
+    /// `max_num_nodes` and `total_edges_emitted` only cover this share until they are set to
+    /// the totals over the workers. Returns an error if a graph file cannot be loaded, and
+    /// panics on graph data that cannot be split between the workers.
+    pub fn gen_initial_graph_partitioned(&mut self, desc: &GraphBenchmarkData, index: usize, peers: usize) -> Result<Vec<WeightedEdge>, GenError> {
+        use GraphBenchmarkData::*;
+        let edges = match desc {
+            RandomGraph { nodes, edges, connected: false, weight_par } => {
+                self.num_nodes = *nodes;
+                self.gen_random_chunks(*nodes, *edges, weight_par, index, peers)
+            }
+            RealWorldGraph { path_to_edge_list, weight_mode: weight_mode @ WeightMode::UseFile }
+            | RealWorldGraph { path_to_edge_list, weight_mode: weight_mode @ WeightMode::ScaleFile(_) } => {
+                let mut edges = GraphLoader::new(index, peers).load_weighted_graph_partitioned(&path_to_edge_list)?;
+                if let WeightMode::ScaleFile(factor) = weight_mode {
+                    edges = scale_weights(edges, *factor);
+                }
+                self.num_nodes = num_nodes_from_edge_list(&edges);
+                edges
+            }
+            SavedGraph { path_to_binary, .. } => {
+                let edges = GraphLoader::new(index, peers).read_binary_graph(&path_to_binary).map_err(GraphLoadError::from)?;
+                self.num_nodes = num_nodes_from_edge_list(&edges);
+                edges
+            }
+            _ => panic!("gen_initial_graph_partitioned called with graph data that cannot be split between workers"),
+        };
+        let edges = if self.symmetric { symmetrize_edges(edges) } else { edges };
+        self.total_edges = edges.len();
+        Ok(edges)
+    }
+
+    /// The chunks of `PARTITION_CHUNK_EDGES` random edges of worker `index` out of `peers`,
+    /// which takes every `peers`th chunk. Each chunk is drawn from its own stream of the seed,
+    /// so the union over the workers does not depend on their number.
+    fn gen_random_chunks(&self, nodes: u32, edges: u32, weight_par: &WeightParameters, index: usize, peers: usize) -> Vec<WeightedEdge> {
+        let num_chunks = (edges + PARTITION_CHUNK_EDGES - 1) / PARTITION_CHUNK_EDGES;
+        let mut data = Vec::new();
+        for chunk in (index as u32 .. num_chunks).step_by(peers) {
+            // Stream 0 is the one of the generator itself, which draws the updates.
+            let mut rng = default_rng(self.seed);
+            rng.set_stream(1 + chunk as u64);
+            let chunk_edges = std::cmp::min(PARTITION_CHUNK_EDGES, edges - chunk * PARTITION_CHUNK_EDGES);
+            data.extend(generate_weighted_graph(&mut rng, nodes, chunk_edges, weight_par));
+        }
+        data
+    }
+
+    /// Number of edges in the graph built from everything generated so far: the initial
+    /// edges, plus the inserted and minus the deleted update edges.
+    pub fn total_edges_emitted(&self) -> usize {
+        self.total_edges
+    }
 
-    pub fn default() -> GraphLoader {
-        GraphLoader {index: 0, peers: 1}
+    /// Number of edges inserted or deleted by all updates generated so far.
+    pub fn update_edges_emitted(&self) -> usize {
+        self.update_edges
     }
 
-    pub fn new(index: usize, peers: usize) -> GraphLoader {
-        GraphLoader {index: index, peers: peers}
+    pub fn gen_graph_updates(& mut self, desc: &GraphBenchmarkUpdates) -> Vec<WeightedEdge> {
+        self.gen_graph_updates_near(desc, &[])
     }
 
-    /// Load from a file containing triplets of numbers: "source target weight"
-    pub fn load_weighted_graph(&self, filename: &str) -> Vec<WeightedEdge> {
-        // Standard io/fs boilerplate.
-        use std::io::{BufRead, BufReader};
-        use std::fs::File;
+    /// As `gen_graph_updates`, but returns an error in place of panicking.
+    pub fn try_gen_graph_updates(& mut self, desc: &GraphBenchmarkUpdates) -> Result<Vec<WeightedEdge>, GenError> {
+        self.try_gen_graph_updates_near(desc, &[])
+    }
 
-        let mut data = Vec::new();
-        let file = BufReader::new(File::open(filename).expect("Could open file"));
-        let lines = file.lines();
-        
-        for (count, read_line) in lines.enumerate() {
-            if count % self.peers == self.index {
-                if let Ok(line) = read_line {
-                    if line.starts_with("#") {
-                        continue;
-                    }
-                    let mut text = line.split_whitespace();
-                    let from = text.next().expect("Must have from node").parse().expect("Invalid from node");
-                    let to = text.next().expect("Must have to node").parse().expect("Invalid to node");
-                    let weight = text.next().expect("Must have node weight").parse().expect("Invalid node weight");
-                    data.push((from, to, weight));
+    /// Generate updates given the nodes currently closest to the source,
+    /// which are the start points of adversarial updates.
+    pub fn gen_graph_updates_near(& mut self, desc: &GraphBenchmarkUpdates, near_nodes: &[Node]) -> Vec<WeightedEdge> {
+        self.try_gen_graph_updates_near(desc, near_nodes).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// As `gen_graph_updates_near`, but returns an error in place of panicking.
+    pub fn try_gen_graph_updates_near(& mut self, desc: &GraphBenchmarkUpdates, near_nodes: &[Node]) -> Result<Vec<WeightedEdge>, GenError> {
+        if self.num_nodes == 0 {
+            return Err(GenError::NotInitialized);
+        }
+        use GraphBenchmarkUpdates::*;
+        let edges = match desc {
+            RandomUpdates{edges_per_update, weight_par, ..} => {
+                generate_weighted_graph(&mut self.rng, self.num_nodes, *edges_per_update, weight_par)
+            }
+            AdversarialUpdates{edges_per_update, weight_par} => {
+                use rand::distributions::{Distribution, Uniform};
+
+                if near_nodes.is_empty() {
+                    return Err(GenError::NoNearNodes);
+                }
+                let dist_from = Uniform::new(0, near_nodes.len());
+                let dist_to = Uniform::new(0 as Node, self.num_nodes);
+                let mut edges = Vec::new();
+                for _ in 0 .. *edges_per_update {
+                    let from = near_nodes[dist_from.sample(&mut self.rng)];
+                    let to = dist_to.sample(&mut self.rng);
+                    edges.push((from, to, weight_par.weight_range.0));
                 }
+                edges
             }
-        }
-        data
+        };
+        let edges = if self.symmetric { symmetrize_edges(edges) } else { edges };
+        self.total_edges += edges.len();
+        self.update_edges += edges.len();
+        Ok(edges)
     }
 
-    /// Load from a file containing pairs of numbers: "source target"
-    pub fn load_unweighted_graph(&self, filename: &str) -> Vec<UnweightedEdge> {
-        // Standard io/fs boilerplate.
-        use std::io::{BufRead, BufReader};
-        use std::fs::File;
+    /// Generate a batch of changes as edges with a diff of 1 for insertions and -1 for deletions.
+    /// A `deletion_ratio` fraction of random updates delete edges drawn from the tracked ones,
+    /// and the rest insert random edges. Adversarial updates only insert, as `gen_graph_updates_near`.
+    pub fn gen_graph_changes_near(& mut self, desc: &GraphBenchmarkUpdates, near_nodes: &[Node]) -> Vec<(WeightedEdge, isize)> {
+        self.try_gen_graph_changes_near(desc, near_nodes).unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        let mut data = Vec::new();
-        let file = BufReader::new(File::open(filename).expect("Could open file"));
-        let lines = file.lines();
+    /// As `gen_graph_changes_near`, but returns an error in place of panicking.
+    pub fn try_gen_graph_changes_near(& mut self, desc: &GraphBenchmarkUpdates, near_nodes: &[Node]) -> Result<Vec<(WeightedEdge, isize)>, GenError> {
+        use rand::distributions::{Distribution, Uniform};
 
-        for (count, read_line) in lines.enumerate() {
-            if count % self.peers == self.index {
-                if let Ok(line) = read_line {
-                    if line.starts_with("#") {
-                        continue;
+        let (edges_per_update, weight_par, deletion_ratio) = match desc {
+            GraphBenchmarkUpdates::RandomUpdates { edges_per_update, weight_par, deletion_ratio } => (*edges_per_update, weight_par, *deletion_ratio),
+            GraphBenchmarkUpdates::AdversarialUpdates { .. } => {
+                let edges = self.try_gen_graph_updates_near(desc, near_nodes)?;
+                return Ok(edges.into_iter().map(|edge| (edge, 1)).collect());
+            }
+        };
+        if self.num_nodes == 0 {
+            return Err(GenError::NotInitialized);
+        }
+        let num_deletions = (deletion_ratio as f64 * edges_per_update as f64).round() as u32;
+        let mut changes = Vec::with_capacity(edges_per_update as usize);
+        if num_deletions > 0 {
+            let present_edges = self.present_edges.as_mut().ok_or(GenError::NoHistory)?;
+            for _ in 0 .. num_deletions {
+                if present_edges.is_empty() {
+                    break;
+                }
+                let index = Uniform::new(0, present_edges.len()).sample(&mut self.rng);
+                let (from, to, weight) = present_edges.swap_remove(index);
+                changes.push(((from, to, weight), -1));
+                if self.symmetric && from != to {
+                    if let Some(index) = present_edges.iter().position(|edge| *edge == (to, from, weight)) {
+                        changes.push((present_edges.swap_remove(index), -1));
                     }
-                    let mut text = line.split_whitespace();
-                    let from = text.next().expect("Must have from node").parse().expect("Invalid from node");
-                    let to = text.next().expect("Must have to node").parse().expect("Invalid to node");
-                    data.push((from, to));
                 }
             }
         }
-        data
+        let mut insertions = generate_weighted_graph(&mut self.rng, self.num_nodes, edges_per_update - num_deletions, weight_par);
+        if self.symmetric {
+            insertions = symmetrize_edges(insertions);
+        }
+        if let Some(present_edges) = &mut self.present_edges {
+            present_edges.extend(insertions.iter().cloned());
+        }
+        self.total_edges = self.total_edges + insertions.len() - changes.len();
+        changes.extend(insertions.into_iter().map(|edge| (edge, 1)));
+        self.update_edges += changes.len();
+        Ok(changes)
     }
 }
 
-pub use rand::SeedableRng;
 
-pub fn default_rng(seed: u64) -> rand_chacha::ChaCha8Rng {
-    rand_chacha::ChaCha8Rng::seed_from_u64(seed)
+/// Distribution of the durations of a set of recorded sub-events. Percentiles are nearest-rank,
+/// so each is one of the durations. All durations are zero if there were no sub-events.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TimingSummary {
+    pub count: usize,
+    pub min: std::time::Duration,
+    pub max: std::time::Duration,
+    pub mean: std::time::Duration,
+    pub median: std::time::Duration,
+    pub p95: std::time::Duration,
+    pub p99: std::time::Duration,
 }
 
-/// Generate a random graph with a given number of vertices and edges
-pub fn generate_unweighted_graph(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, num_edges: u32) -> Vec<UnweightedEdge> {
-    use rand::distributions::{Distribution, Uniform};
+impl TimingSummary {
+    pub fn from_durations(durations: &[std::time::Duration]) -> TimingSummary {
+        if durations.is_empty() {
+            return TimingSummary::default();
+        }
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let percentile = |p: f64| sorted[((p / 100.0 * sorted.len() as f64).ceil() as usize).max(1) - 1];
+        TimingSummary {
+            count: sorted.len(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<std::time::Duration>() / sorted.len() as u32,
+            median: percentile(50.0),
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+        }
+    }
+}
 
-    let dist = Uniform::new(0 as Node, num_nodes as Node);
-    let mut edges = Vec::new();
-    for _ in 0 .. num_edges {
-        let from = dist.sample(rng);
-        let to = dist.sample(rng);
-        edges.push((from, to));
+impl std::fmt::Display for TimingSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "count {} min {:?} median {:?} mean {:?} p95 {:?} p99 {:?} max {:?}",
+            self.count, self.min, self.median, self.mean, self.p95, self.p99, self.max
+        )
     }
-    edges
 }
 
-/// Generate a random graph with a given number of vertices, edges and weights for the edges.
-pub fn generate_weighted_graph(rng: &mut rand_chacha::ChaCha8Rng, num_nodes: u32, num_edges: u32, weight_range: (Weight, Weight)) -> Vec<WeightedEdge> {
-    use rand::distributions::{Distribution, Uniform};
+/// Edges processed per second by the initial computation and by the update rounds,
+/// or `None` if there was no such sub-event or it took no measurable time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Throughput {
+    pub initial: Option<f64>,
+    pub updates: Option<f64>,
+}
 
-    let dist = Uniform::new(0 as Node, num_nodes as Node);
-    let dist_w = Uniform::new(weight_range.0, weight_range.1);
-    let mut edges = Vec::new();
-    for _ in 0 .. num_edges {
-        let from = dist.sample(rng);
-        let to = dist.sample(rng);
-        let w = dist_w.sample(rng);
-        edges.push((from, to, w));
+impl std::fmt::Display for Throughput {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let format = |throughput: Option<f64>| match throughput {
+            Some(throughput) => format!("{:.0} edges/sec", throughput),
+            None => "-".to_string(),
+        };
+        write!(f, "initial {} updates {}", format(self.initial), format(self.updates))
     }
-    edges
 }
 
-pub fn generate_weights_for_graph(rng: &mut rand_chacha::ChaCha8Rng, edges: Vec<UnweightedEdge>, weight_range: (Weight, Weight)) -> Vec<WeightedEdge> {
-    use rand::distributions::{Distribution, Uniform};
+/// A timed sub-event, stored as offsets from the start of the timer.
+struct TraceSpan {
+    name: String,
+    begin: std::time::Duration,
+    end: std::time::Duration,
+}
 
-    let dist_w = Uniform::new(weight_range.0, weight_range.1);
-    edges.into_iter().map(|(from, to)| (from, to,  dist_w.sample(rng))).collect()
+/// Times sub-events and writes a line for each to its output, standard output by default.
+pub struct SubEventTimer<W: std::io::Write = std::io::Stdout> {
+    total_timer: std::time::Instant,
+    // Shared between the timely worker threads, hence the mutex.
+    spans: Option<std::sync::Mutex<Vec<TraceSpan>>>,
+    cpu_time: bool,
+    memory: bool,
+    // Shared like the spans, and locked for each line so lines are never interleaved.
+    output: std::sync::Mutex<W>,
 }
 
-#[derive(Clone, Copy, Debug)]
-enum GraphDataType {
-    Random, RealWorld
+impl SubEventTimer {
+
+    pub fn new_timer() -> SubEventTimer {
+        SubEventTimer {
+            total_timer: std::time::Instant::now(),
+            spans: None,
+            cpu_time: false,
+            memory: false,
+            output: std::sync::Mutex::new(std::io::stdout()),
+        }
+    }
+
+    /// A timer that records every sub-event, for `to_json` and `write_chrome_trace`.
+    pub fn new_recording() -> SubEventTimer {
+        SubEventTimer::new_timer().with_span_trace(true)
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct WeightParameters {
-    pub weight_range: (u32, u32),
-    pub rng_seed: u64,
+impl<W: std::io::Write> SubEventTimer<W> {
+
+    /// Write the timing lines to `output` instead, for example a file per worker or a
+    /// buffered writer, which is flushed when the timer is dropped.
+    pub fn with_output<V: std::io::Write>(self, output: V) -> SubEventTimer<V> {
+        SubEventTimer {
+            total_timer: self.total_timer,
+            spans: self.spans,
+            cpu_time: self.cpu_time,
+            memory: self.memory,
+            output: std::sync::Mutex::new(output),
+        }
+    }
+
+    /// Enable recording of each sub-event as a span for `write_chrome_trace`.
+    pub fn with_span_trace(mut self, enabled: bool) -> SubEventTimer<W> {
+        self.spans = if enabled { Some(std::sync::Mutex::new(Vec::new())) } else { None };
+        self
+    }
+
+    /// Also print the CPU time spent in each sub-event, where the platform can measure it.
+    /// The CPU time is that of the whole process, so it includes all worker threads.
+    pub fn with_cpu_time(mut self, enabled: bool) -> SubEventTimer<W> {
+        self.cpu_time = enabled;
+        self
+    }
+
+    /// Also print the resident memory of the process after each sub-event, and how much it
+    /// changed during it, where the platform can measure it.
+    pub fn with_memory(mut self, enabled: bool) -> SubEventTimer<W> {
+        self.memory = enabled;
+        self
+    }
+
+    /// Timing utilities
+    pub fn time_subevent<G, F: FnMut() -> G>(&self, event: &str, func: F) -> G {
+        self.time_subevent_noted(event, None, func)
+    }
+
+    /// Like `time_subevent`, but also prints the number of edges in the graph after the
+    /// sub-event on the same line, from which the throughput can be computed.
+    pub fn time_subevent_with_edges<G, F: FnMut() -> G>(&self, event: &str, num_edges: usize, func: F) -> G {
+        self.time_subevent_noted(event, Some(format!("Edges: {}", num_edges)), func)
+    }
+
+    fn time_subevent_noted<G, F: FnMut() -> G>(&self, event: &str, note: Option<String>, mut func: F) -> G {
+        let begin = self.elapsed();
+        let cpu_begin = if self.cpu_time { process_cpu_time() } else { None };
+        let rss_begin = if self.memory { current_rss() } else { None };
+        let timer = std::time::Instant::now();
+        let res = func();
+        let elapse = timer.elapsed();
+        let mut line = format!("Total: {:15}{:10}{:15}", format!("{:?}", self.elapsed()), event, format!("{:?}", elapse));
+        if let Some(cpu) = cpu_begin.and_then(|cpu_begin| process_cpu_time().map(|cpu_end| cpu_end - cpu_begin)) {
+            line.push_str(&format!(" CPU: {:?}", cpu));
+        }
+        if let Some(rss_begin) = rss_begin {
+            if let Some(rss_end) = current_rss() {
+                let delta = rss_end as f64 - rss_begin as f64;
+                line.push_str(&format!(" RSS: {:.1} MiB ({:+.1} MiB)", to_mib(rss_end as f64), to_mib(delta)));
+            }
+        }
+        if let Some(note) = note {
+            line.push_str(&format!(" {}", note));
+        }
+        let mut output = self.output.lock().expect("Timer output lock poisoned");
+        writeln!(output, "{}", line).expect("Could not write timing line");
+        drop(output);
+        if let Some(spans) = &self.spans {
+            let span = TraceSpan { name: event.to_string(), begin: begin, end: begin + elapse };
+            spans.lock().expect("Span trace lock poisoned").push(span);
+        }
+        res
+    }
+
+    /// Write the recorded spans as begin/end events in the Chrome tracing JSON format,
+    /// which can be opened in chrome://tracing or Perfetto.
+    pub fn write_chrome_trace(&self, path: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let spans = match &self.spans {
+            Some(spans) => spans.lock().expect("Span trace lock poisoned"),
+            None => panic!("write_chrome_trace called on a timer without span tracing"),
+        };
+        let mut events = Vec::new();
+        for span in spans.iter() {
+            let name = escape_json(&span.name);
+            events.push(format!("{{\"name\":\"{}\",\"ph\":\"B\",\"ts\":{},\"pid\":0,\"tid\":0}}", name, span.begin.as_micros()));
+            events.push(format!("{{\"name\":\"{}\",\"ph\":\"E\",\"ts\":{},\"pid\":0,\"tid\":0}}", name, span.end.as_micros()));
+        }
+        let mut file = std::fs::File::create(path)?;
+        writeln!(file, "{{\"traceEvents\":[{}]}}", events.join(","))
+    }
+
+    /// The recorded sub-events and their durations, in the order they finished.
+    pub fn recorded(&self) -> Vec<(String, std::time::Duration)> {
+        match &self.spans {
+            Some(spans) => spans
+                .lock()
+                .expect("Span trace lock poisoned")
+                .iter()
+                .map(|span| (span.name.clone(), span.end - span.begin))
+                .collect(),
+            None => panic!("recorded called on a timer without recording"),
+        }
+    }
+
+    /// Summary of the durations of the recorded sub-events whose name starts with
+    /// `event_prefix`, for example "N " for the update rounds.
+    pub fn summary(&self, event_prefix: &str) -> TimingSummary {
+        let durations: Vec<std::time::Duration> = self
+            .recorded()
+            .into_iter()
+            .filter(|(event, _)| event.starts_with(event_prefix))
+            .map(|(_, elapse)| elapse)
+            .collect();
+        TimingSummary::from_durations(&durations)
+    }
+
+    /// Throughput of the recorded "Initial" sub-event over `initial_edges` edges, and of the
+    /// update rounds, the sub-events starting with "N ", over `update_edges` edges together.
+    pub fn throughput(&self, initial_edges: usize, update_edges: usize) -> Throughput {
+        let recorded = self.recorded();
+        let per_second = |event_prefix: &str, num_edges: usize| {
+            let elapse: std::time::Duration = recorded
+                .iter()
+                .filter(|(event, _)| event.starts_with(event_prefix))
+                .map(|(_, elapse)| *elapse)
+                .sum();
+            if elapse > std::time::Duration::from_secs(0) {
+                Some(num_edges as f64 / elapse.as_secs_f64())
+            } else {
+                None
+            }
+        };
+        Throughput { initial: per_second("Initial", initial_edges), updates: per_second("N ", update_edges) }
+    }
+
+    /// The recorded sub-events as a JSON array like `[{"event":"Loading","nanos":123}]`.
+    pub fn to_json(&self) -> String {
+        let events: Vec<String> = self
+            .recorded()
+            .iter()
+            .map(|(event, elapse)| format!("{{\"event\":\"{}\",\"nanos\":{}}}", escape_json(event), elapse.as_nanos()))
+            .collect();
+        format!("[{}]", events.join(","))
+    }
+
+    pub fn elapsed(&self) -> std::time::Duration {
+        self.total_timer.elapsed()
+    }
 }
 
-#[derive(Debug)]
-pub enum GraphBenchmarkData {
-   RandomGraph { nodes: u32, edges: u32, weight_par: WeightParameters },
-   RealWorldGraph { path_to_edge_list: String, weight_par: Option<WeightParameters> },
+/// Resident set size of the process in bytes, read from `/proc/self/statm`.
+/// Only available on Linux, `None` elsewhere or if it cannot be read.
+#[cfg(target_os = "linux")]
+pub fn current_rss() -> Option<usize> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    // The second field is the number of resident pages.
+    let pages: usize = statm.split_whitespace().nth(1)?.parse().ok()?;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if page_size <= 0 {
+        return None;
+    }
+    Some(pages * page_size as usize)
 }
 
-#[derive(Debug)]
-pub enum GraphBenchmarkUpdates {
-    RandomUpdates { edges_per_update: u32, weight_par: WeightParameters },
+/// Resident memory is not measured on this platform.
+#[cfg(not(target_os = "linux"))]
+pub fn current_rss() -> Option<usize> {
+    None
 }
 
-#[derive(Debug)]
-pub struct SearchQuery {
-    pub source: u32,
-    pub target: u32,
+fn to_mib(bytes: f64) -> f64 {
+    bytes / (1024.0 * 1024.0)
 }
 
-#[derive(Debug)]
-pub struct BenchmarkDescription {
-    pub graph_data: GraphBenchmarkData,
-    pub graph_updates: GraphBenchmarkUpdates,
-    pub num_rounds: u32,
-    pub search_query: SearchQuery,
-    pub inspect_results: bool,
+/// User plus system CPU time used by the process so far.
+#[cfg(unix)]
+fn process_cpu_time() -> Option<std::time::Duration> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return None;
+    }
+    let to_duration = |time: libc::timeval| {
+        std::time::Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000)
+    };
+    Some(to_duration(usage.ru_utime) + to_duration(usage.ru_stime))
 }
 
-pub fn extract_weight_range(data: &GraphBenchmarkData) -> (u32, u32) {
-    extract_weight_parameters(data).weight_range
+/// CPU time is not measured on this platform.
+#[cfg(not(unix))]
+fn process_cpu_time() -> Option<std::time::Duration> {
+    None
 }
 
-fn extract_weight_parameters(data: &GraphBenchmarkData) -> WeightParameters {
-    use GraphBenchmarkData::*;
-    match data {
-        RandomGraph{weight_par, ..} => *weight_par,
-        RealWorldGraph{weight_par, ..} => weight_par.unwrap_or(WeightParameters{ weight_range: (0u32, 10u32), rng_seed: 10u64 }),
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
     }
+    escaped
 }
 
-/// Common command line argument parsers. Makes sure we parse the same arguments
-/// in all benchmarking executables.
-pub fn parse_graph_benchmark_arguments(mut arguments: std::env::Args) -> BenchmarkDescription {
-    arguments.next().expect("Command line argument should contain an executable name.");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let type_of_data: String = arguments.next().expect("Did not pass type of graph data");
-    let graph_type = match type_of_data.as_str() {
-        "real" => GraphDataType::RealWorld,
-        "random" => GraphDataType::Random,
-        _ => panic!("Invalid type of data passed. Please use one of: real, random"),
-    };
+    /// Write `contents` to a file of the test's own in the temporary directory and return its path.
+    fn temp_file(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("graph_utility-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).expect("Could not write temporary file");
+        path.to_str().expect("Temporary path must be valid UTF-8").to_string()
+    }
 
-    let graph_data = match graph_type {
-        GraphDataType::Random => {
-            let nodes: u32 = arguments.next().expect("No number of nodes passed").parse().expect("Invalid argument passed to number of nodes");
-            let edges: u32 = arguments.next().expect("No number of edges passed").parse().expect("Invalid argument passed to number of edges");
-            let lower_weight: u32 = arguments.next().expect("No weight lower bound passed").parse().expect("Invalid argument passed to lower bound weight");
-            let upper_weight: u32 = arguments.next().expect("No weight upper bound passed").parse().expect("Invalid argument passed to upper bound weight");
-            if lower_weight >= upper_weight {
-                panic!("Lower weight range must be less than upper weight range");
+    #[test]
+    fn partitioned_loading_covers_every_edge_once() {
+        // Lines of different lengths, so the byte ranges of the peers begin inside lines.
+        let lines = "# comment\n0 1 5\n1 22 333\n\n22 4444 1\n7\n4444 5 55555\n5 0 7\n0 0 12\n6 7 8";
+        let peers = 4;
+        for (name, contents) in [("partitioned-newline", format!("{}\n", lines)), ("partitioned", lines.to_string())].iter() {
+            let bytes = contents.as_bytes();
+            let straddled = (1..peers).any(|peer| bytes[bytes.len() * peer / peers - 1] != b'\n');
+            assert!(straddled, "Some range must begin inside a line");
+
+            let path = temp_file(name, contents);
+            let mut single = GraphLoader::default().load_weighted_graph(&path).unwrap();
+            let mut partitioned = Vec::new();
+            for index in 0..peers {
+                partitioned.extend(GraphLoader::new(index, peers).load_weighted_graph_partitioned(&path).unwrap());
             }
-            GraphBenchmarkData::RandomGraph {nodes: nodes, edges: edges, weight_par: WeightParameters{ weight_range: (lower_weight, upper_weight), rng_seed: 10 } }
+            std::fs::remove_file(&path).unwrap();
+            single.sort();
+            partitioned.sort();
+            assert_eq!(single.len(), 7);
+            assert_eq!(partitioned, single);
         }
-        GraphDataType::RealWorld => {
-            let graph_file: String = arguments.next().expect("No path to graph file given");
-            let path_to_file = std::path::Path::new(&graph_file);
-            if !path_to_file.exists() {
-                panic!("Graph file {:?} does not exist", graph_file);
-            }
-            let generate_weights: bool = arguments.next().expect("No weight generation passed") == "generate";
-            let weight_par  = if generate_weights {
-                let lower_weight: u32 = arguments.next().expect("No weight lower bound passed").parse().expect("Invalid argument passed to lower bound weight");
-                let upper_weight: u32 = arguments.next().expect("No weight upper bound passed").parse().expect("Invalid argument passed to upper bound weight");
-                if lower_weight >= upper_weight {
-                    panic!("Lower weight range must be less than upper weight range");
-                }
-                Some(WeightParameters{ weight_range: (lower_weight, upper_weight), rng_seed: 10 })
-            } else {
-                None
-            };
-            GraphBenchmarkData::RealWorldGraph { path_to_edge_list: graph_file, weight_par: weight_par }
+    }
+
+    #[test]
+    fn dedup_and_drop_self_loops() {
+        let path = temp_file("cleaning", "0 1 5\n1 1 2\n0 1 3\n2 0 4\n1 1 1\n2 0 4\n");
+        let load = |dedup: bool, drop_self_loops: bool| {
+            GraphLoader::default()
+                .with_dedup(dedup)
+                .with_drop_self_loops(drop_self_loops)
+                .load_weighted_graph(&path)
+                .unwrap()
+        };
+        let plain = load(false, false);
+        let deduped = load(true, false);
+        let without_loops = load(false, true);
+        let cleaned = load(true, true);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(plain, vec![(0, 1, 5), (1, 1, 2), (0, 1, 3), (2, 0, 4), (1, 1, 1), (2, 0, 4)]);
+        // Duplicates keep their lowest weight, and the edges come out sorted.
+        assert_eq!(deduped, vec![(0, 1, 3), (1, 1, 1), (2, 0, 4)]);
+        assert_eq!(without_loops, vec![(0, 1, 5), (0, 1, 3), (2, 0, 4), (2, 0, 4)]);
+        assert_eq!(cleaned, vec![(0, 1, 3), (2, 0, 4)]);
+    }
+
+    #[test]
+    fn adjacency_matrix() {
+        let path = temp_file("adjacency", "# 3 nodes\n0 2 0\n\n0 0 7\n1 0 3\n");
+        assert_eq!(GraphLoader::default().load_adjacency_matrix(&path).unwrap(), vec![(0, 1, 2), (1, 2, 7), (2, 0, 1), (2, 2, 3)]);
+        // Every row is checked, also the rows of other peers.
+        assert_eq!(GraphLoader::new(1, 2).load_adjacency_matrix(&path).unwrap(), vec![(1, 2, 7)]);
+        std::fs::remove_file(&path).unwrap();
+
+        let path = temp_file("adjacency-row-length", "0 1\n1 0 1\n");
+        match GraphLoader::default().load_adjacency_matrix(&path) {
+            Err(GraphLoadError::RowLength { line: 2, entries: 3, expected: 2 }) => (),
+            other => panic!("expected a row length error, got {:?}", other),
         }
-    };
+        std::fs::remove_file(&path).unwrap();
 
-    let num_rounds: u32 = arguments.next().expect("No number of rounds").parse().expect("Invalid argument passed to number of rounds");
-    let edges_per_update: u32 = arguments.next().expect("No number of edges per round").parse().expect("Invalid argument passed to edges per round");
+        let path = temp_file("adjacency-not-square", "0 1 0\n1 0 1\n");
+        match GraphLoader::default().load_adjacency_matrix(&path) {
+            Err(GraphLoadError::NotSquare { rows: 2, columns: 3 }) => (),
+            other => panic!("expected a non-square matrix error, got {:?}", other),
+        }
+        std::fs::remove_file(&path).unwrap();
 
-    let source: u32 = arguments.next().expect("No source node given").parse().expect("Invalid argument passed to source node");
+        let path = temp_file("adjacency-not-numeric", "0 1\n1 x\n");
+        match GraphLoader::default().load_adjacency_matrix(&path) {
+            Err(GraphLoadError::ParseInt { line: 2, .. }) => (),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
 
-    let target: u32 = arguments.next().expect("No target node given").parse().expect("Invalid argument passed to target node");
+    #[test]
+    fn metis_files() {
+        let load = |name: &str, contents: &str| {
+            let path = temp_file(name, contents);
+            let edges = GraphLoader::default().load_metis(&path);
+            std::fs::remove_file(&path).unwrap();
+            edges
+        };
+        // Node 2 has no neighbours, and blank lines may follow the last node.
+        let edges = load("metis", "% comment\n3 2\n2 3\n\n1\n\n").unwrap();
+        assert_eq!(edges, vec![(0, 1, 1), (0, 2, 1), (2, 0, 1)]);
+        // Two node weights skipped before the neighbours, each followed by its edge weight.
+        let edges = load("metis-weights", "3 2 011 2\n5 6 2 4\n1 1 1 4 3 9\n7 7\n").unwrap();
+        assert_eq!(edges, vec![(0, 1, 4), (1, 0, 4), (1, 2, 9)]);
+        // A node size skipped, and a single node weight without ncon.
+        let edges = load("metis-sizes", "2 1 110\n3 8 2\n1 1 1\n").unwrap();
+        assert_eq!(edges, vec![(0, 1, 1), (1, 0, 1)]);
 
-    let graph_updates = GraphBenchmarkUpdates::RandomUpdates{edges_per_update: edges_per_update, weight_par: extract_weight_parameters(&graph_data) };
+        match load("metis-empty", "% no header\n") {
+            Err(GraphLoadError::InvalidHeader { line: 1, .. }) => (),
+            other => panic!("expected a header error, got {:?}", other),
+        }
+        match load("metis-header", "% comment\n3\n2\n") {
+            Err(GraphLoadError::InvalidHeader { line: 2, .. }) => (),
+            other => panic!("expected a header error, got {:?}", other),
+        }
+        match load("metis-fmt", "2 1 2\n2\n1\n") {
+            Err(GraphLoadError::Unsupported { line: 1, .. }) => (),
+            other => panic!("expected an unsupported fmt error, got {:?}", other),
+        }
+        match load("metis-missing-weight", "2 1 1\n2\n1 5\n") {
+            Err(GraphLoadError::MissingField { line: 2 }) => (),
+            other => panic!("expected a missing weight error, got {:?}", other),
+        }
+        match load("metis-out-of-range", "2 1\n2\n3\n") {
+            Err(GraphLoadError::NodeOutOfRange { line: 3, node: 3, num_nodes: 2 }) => (),
+            other => panic!("expected an out of range error, got {:?}", other),
+        }
+        match load("metis-zero", "2 1\n0\n1\n") {
+            Err(GraphLoadError::NodeOutOfRange { line: 2, node: 0, num_nodes: 2 }) => (),
+            other => panic!("expected an out of range error, got {:?}", other),
+        }
+        match load("metis-short", "3 1\n2\n1\n") {
+            Err(GraphLoadError::WrongCount { found: 2, expected: 3, .. }) => (),
+            other => panic!("expected a node count error, got {:?}", other),
+        }
+        match load("metis-long", "1 0\n\n1\n\n") {
+            Err(GraphLoadError::WrongCount { found: 2, expected: 1, .. }) => (),
+            other => panic!("expected a node count error, got {:?}", other),
+        }
+    }
 
-    let search_query = SearchQuery {source: source, target: target};
+    #[test]
+    fn matrix_market_files() {
+        let load = |name: &str, contents: &str| {
+            let path = temp_file(name, contents);
+            let edges = GraphLoader::default().load_matrix_market(&path);
+            std::fs::remove_file(&path).unwrap();
+            edges
+        };
+        // 1-indexed entries, with values rounded to weights.
+        let edges = load("mtx", "%%MatrixMarket matrix coordinate real general\n% comment\n3 3 3\n1 2 2.4\n\n3 1 7\n2 2 0.5\n").unwrap();
+        assert_eq!(edges, vec![(0, 1, 2), (2, 0, 7), (1, 1, 1)]);
+        // The reverse of every entry off the diagonal, and weight 1 for patterns.
+        let edges = load("mtx-symmetric", "%%MatrixMarket matrix coordinate pattern symmetric\n3 3 2\n2 1\n3 3\n").unwrap();
+        assert_eq!(edges, vec![(1, 0, 1), (0, 1, 1), (2, 2, 1)]);
 
-    let inspect = arguments.next().map(|x| x == "inspect").unwrap_or(false);
+        for (name, banner) in [("mtx-no-banner", "3 3 0"), ("mtx-vector", "%%MatrixMarket vector coordinate real general")].iter() {
+            match load(name, &format!("{}\n3 3 0\n", banner)) {
+                Err(GraphLoadError::InvalidHeader { line: 1, .. }) => (),
+                other => panic!("expected a banner error, got {:?}", other),
+            }
+        }
+        let unsupported = ["array real general", "coordinate complex general", "coordinate real hermitian"];
+        for banner in unsupported.iter() {
+            match load("mtx-unsupported", &format!("%%MatrixMarket matrix {}\n3 3 0\n", banner)) {
+                Err(GraphLoadError::Unsupported { line: 1, .. }) => (),
+                other => panic!("expected an unsupported banner error, got {:?}", other),
+            }
+        }
+        match load("mtx-dimensions", "%%MatrixMarket matrix coordinate real general\n3 3\n1 2 1\n") {
+            Err(GraphLoadError::InvalidHeader { line: 2, .. }) => (),
+            other => panic!("expected a dimensions error, got {:?}", other),
+        }
+        match load("mtx-out-of-range", "%%MatrixMarket matrix coordinate real general\n2 3 1\n0 3 1\n") {
+            Err(GraphLoadError::NodeOutOfRange { line: 3, node: 0, num_nodes: 3 }) => (),
+            other => panic!("expected an out of range error, got {:?}", other),
+        }
+        match load("mtx-value", "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 2 x\n") {
+            Err(GraphLoadError::ParseFloat { line: 3, .. }) => (),
+            other => panic!("expected a value error, got {:?}", other),
+        }
+        match load("mtx-short", "%%MatrixMarket matrix coordinate integer general\n2 2 3\n1 2 1\n2 1 1\n") {
+            Err(GraphLoadError::WrongCount { found: 2, expected: 3, .. }) => (),
+            other => panic!("expected an entry count error, got {:?}", other),
+        }
+    }
 
-    BenchmarkDescription{graph_data: graph_data, graph_updates: graph_updates, num_rounds: num_rounds, search_query: search_query, inspect_results: inspect}
-}
+    #[test]
+    fn capacitated_loading_is_cleaned() {
+        let path = temp_file("capacitated", "0 1 5 10\n1 1 2 3\n0 1 3 20\n2 0 4 1\n");
+        let load = |loader: GraphLoader| loader.load_capacitated_graph(&path).unwrap();
+        assert_eq!(load(GraphLoader::default()), vec![(0, 1, 5, 10), (1, 1, 2, 3), (0, 1, 3, 20), (2, 0, 4, 1)]);
+        assert_eq!(load(GraphLoader::default().with_dedup(true)), vec![(0, 1, 3, 20), (1, 1, 2, 3), (2, 0, 4, 1)]);
+        assert_eq!(load(GraphLoader::default().with_drop_self_loops(true)), vec![(0, 1, 5, 10), (0, 1, 3, 20), (2, 0, 4, 1)]);
+        let symmetric = load(GraphLoader::default().with_symmetric(true));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(symmetric[4..].to_vec(), vec![(1, 0, 5, 10), (1, 0, 3, 20), (0, 2, 4, 1)]);
+    }
 
-pub struct GraphDataGenerator {
-    rng: rand_chacha::ChaCha8Rng,
-    num_nodes: u32, 
-}
+    #[test]
+    fn temporal_and_signed_loading_is_cleaned() {
+        let path = temp_file("temporal", "0 1 5 9\n1 1 2 3\n0 1 5 7\n0 1 6 7\n");
+        let load = |loader: GraphLoader| loader.load_temporal_graph(&path).unwrap();
+        // Connections at other departure times are not duplicates.
+        assert_eq!(load(GraphLoader::default().with_dedup(true)), vec![(0, 1, 5, 7), (0, 1, 6, 7), (1, 1, 2, 3)]);
+        assert_eq!(load(GraphLoader::default().with_drop_self_loops(true)), vec![(0, 1, 5, 9), (0, 1, 5, 7), (0, 1, 6, 7)]);
+        assert_eq!(load(GraphLoader::default().with_symmetric(true))[4..].to_vec(), vec![(1, 0, 5, 9), (1, 0, 5, 7), (1, 0, 6, 7)]);
+        std::fs::remove_file(&path).unwrap();
 
-fn num_nodes_from_edge_list(edges: &Vec<WeightedEdge>) -> u32 {
-    let mut max_node = 0;
-    for (from, to, ..) in edges.iter() {
-        max_node = std::cmp::max(max_node, *from);
-        max_node = std::cmp::max(max_node, *to);
+        let path = temp_file("signed", "0 1 -5\n1 1 2\n0 1 3\n");
+        let signed = GraphLoader::default().with_symmetric(true).with_dedup(true).load_weighted_graph_signed(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(signed, vec![(0, 1, -5), (1, 0, -5), (1, 1, 2)]);
     }
-    max_node
-}
 
-impl GraphDataGenerator {
+    #[test]
+    fn node_count_is_largest_index_plus_one() {
+        assert_eq!(num_nodes_from_edge_list(&[]), 0);
+        assert_eq!(num_nodes_from_edge_list(&[(0, 0, 1)]), 1);
+        assert_eq!(num_nodes_from_edge_list(&[(2, 7, 1), (5, 3, 1)]), 8);
+    }
 
-    pub fn new_from_seed(seed: u64) -> GraphDataGenerator {
-        GraphDataGenerator { rng: default_rng(seed), num_nodes: 0 }
+    #[test]
+    fn weight_histogram_buckets() {
+        assert_eq!(weight_histogram(&[], 4), vec![]);
+        // A single weight gives a single bucket, however many are asked for.
+        assert_eq!(weight_histogram(&[(0, 1, 7), (1, 2, 7)], 4), vec![(7, 2)]);
+        // The range 1..=10 in buckets of 3 weights: 10 starts the last bucket, 4 and 7 the others.
+        let edges: Vec<WeightedEdge> = [1, 3, 4, 6, 7, 9, 10, 10].iter().map(|weight| (0, 1, *weight)).collect();
+        assert_eq!(weight_histogram(&edges, 4), vec![(1, 2), (4, 2), (7, 2), (10, 2)]);
+        // Fewer weights in the range than buckets.
+        assert_eq!(weight_histogram(&[(0, 1, 2), (1, 2, 3)], 10), vec![(2, 1), (3, 1)]);
+        // The whole range of weights, without overflowing.
+        assert_eq!(weight_histogram(&[(0, 1, 0), (1, 2, u32::max_value())], 2), vec![(0, 1), (1 << 31, 1)]);
     }
 
-    pub fn gen_initial_graph(& mut self, desc: &GraphBenchmarkData) -> Vec<WeightedEdge> {
-        use GraphBenchmarkData::*;
-        match desc {
-            RandomGraph {nodes, edges, weight_par} => {
-                // Update the number of nodes
-                self.num_nodes = *nodes;
-                generate_weighted_graph(&mut self.rng, *nodes, *edges, weight_par.weight_range)
-            }
-            RealWorldGraph { path_to_edge_list, weight_par } => {
-                let loader = GraphLoader::default();
-                let edges = match &weight_par {
-                    None => loader.load_weighted_graph(&path_to_edge_list),
-                    Some(par) => {
-                        generate_weights_for_graph(&mut self.rng, loader.load_unweighted_graph(&path_to_edge_list), par.weight_range)
-                    }
-                };
-                self.num_nodes = num_nodes_from_edge_list(&edges);
-                edges
-            }
-        }
+    #[test]
+    fn distances_are_validated() {
+        let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 7)];
+        let correct: std::collections::HashMap<Node, u64> = [(0, 0), (1, 4), (2, 5)].iter().cloned().collect();
+        assert_eq!(validate_distances(&edges, &[0], &correct), Ok(3));
+        // A wrong distance, a missing reachable node and a node beyond the graph.
+        let wrong: std::collections::HashMap<Node, u64> = [(0, 0), (2, 7), (9, 1)].iter().cloned().collect();
+        let mismatches = DistanceMismatches(vec![(1, None, Some(4)), (2, Some(7), Some(5)), (9, Some(1), None)]);
+        assert_eq!(validate_distances(&edges, &[0], &wrong), Err(mismatches));
     }
-    
-    pub fn max_num_nodes(&self) -> u32 {
-        self.num_nodes
+
+    #[test]
+    fn written_graphs_load_back() {
+        let weighted = vec![(3, 0, 7), (0, 1, 0), (1, 1, 4294967295), (0, 1, 2)];
+        let unweighted: Vec<UnweightedEdge> = weighted.iter().map(|(from, to, _)| (*from, *to)).collect();
+
+        let path = temp_file("round-trip-weighted", "");
+        write_weighted_graph(&weighted, &path).unwrap();
+        assert_eq!(GraphLoader::default().load_weighted_graph(&path).unwrap(), weighted);
+        std::fs::remove_file(&path).unwrap();
+
+        let path = temp_file("round-trip-unweighted", "");
+        write_unweighted_graph(&unweighted, &path).unwrap();
+        assert_eq!(GraphLoader::default().load_unweighted_graph(&path).unwrap(), unweighted);
+        std::fs::remove_file(&path).unwrap();
     }
 
-    pub fn gen_graph_updates(& mut self, desc: &GraphBenchmarkUpdates) -> Vec<WeightedEdge> {
-        if self.num_nodes == 0 {
-            panic!("gen_graph_updates called before gen_initial_graph");
-        }
-        use GraphBenchmarkUpdates::*;
-        let RandomUpdates{edges_per_update, weight_par, ..} = desc;
-        {
-            generate_weighted_graph(&mut self.rng, self.num_nodes, *edges_per_update, weight_par.weight_range)
+    #[test]
+    fn partitioned_generation_does_not_depend_on_peers() {
+        // Several chunks, the last one partial, so every peer draws some of them.
+        let weight_par = WeightParameters { weight_range: (1, 10), rng_seed: 10, distribution: WeightDist::Uniform };
+        let graph = GraphBenchmarkData::RandomGraph { nodes: 1000, edges: 5 * PARTITION_CHUNK_EDGES + 123, connected: false, weight_par: weight_par };
+        for symmetric in [false, true].iter() {
+            let generate = |index: usize, peers: usize| {
+                GraphDataGenerator::new_from_seed(7).with_symmetric(*symmetric).gen_initial_graph_partitioned(&graph, index, peers).unwrap()
+            };
+            let mut single = generate(0, 1);
+            let peers = 4;
+            let mut union: Vec<WeightedEdge> = (0..peers).flat_map(|index| generate(index, peers)).collect();
+            single.sort();
+            union.sort();
+            assert_eq!(union.len(), single.len());
+            assert!(union == single, "The union of {} shares differs from the graph of one peer", peers);
         }
     }
-}
 
+    #[test]
+    fn timing_summary_percentiles() {
+        use std::time::Duration;
 
-pub struct SubEventTimer {
-    total_timer: std::time::Instant,
-}
+        // Shuffled, as the summary sorts the durations itself.
+        let durations: Vec<Duration> = (1..=100u64).map(|ms| Duration::from_millis((ms * 37) % 101)).collect();
+        let summary = TimingSummary::from_durations(&durations);
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.min, Duration::from_millis(1));
+        assert_eq!(summary.max, Duration::from_millis(100));
+        assert_eq!(summary.mean, Duration::from_micros(50_500));
+        assert_eq!(summary.median, Duration::from_millis(50));
+        assert_eq!(summary.p95, Duration::from_millis(95));
+        assert_eq!(summary.p99, Duration::from_millis(99));
 
-impl SubEventTimer {
+        let single = TimingSummary::from_durations(&[Duration::from_millis(7)]);
+        for duration in [single.min, single.mean, single.median, single.p95, single.p99, single.max].iter() {
+            assert_eq!(*duration, Duration::from_millis(7));
+        }
+    }
 
-    pub fn new_timer() -> SubEventTimer {
-        SubEventTimer { total_timer: std::time::Instant::now() }
+    #[test]
+    fn timing_summary_of_no_durations() {
+        assert_eq!(TimingSummary::from_durations(&[]), TimingSummary::default());
+        assert_eq!(TimingSummary::from_durations(&[]).count, 0);
     }
 
-    /// Timing utilities
-    pub fn time_subevent<G, F: FnMut() -> G>(&self, event: &str, mut func: F) -> G {
-        let timer = std::time::Instant::now();
-        let res = func();
-        let elapse = timer.elapsed();
-        println!("Total: {:15}{:10}{:15}", format!("{:?}", self.elapsed()), event, format!("{:?}", elapse));
-        res
+    #[test]
+    fn deletions_without_history_are_an_error() {
+        let weight_par = WeightParameters { weight_range: (1, 10), rng_seed: 10, distribution: WeightDist::Uniform };
+        let graph = GraphBenchmarkData::RandomGraph { nodes: 10, edges: 20, connected: false, weight_par: weight_par };
+        let updates = GraphBenchmarkUpdates::RandomUpdates { edges_per_update: 4, weight_par: weight_par, deletion_ratio: 0.5 };
+        let mut gen = GraphDataGenerator::new_from_seed(7);
+        gen.gen_initial_graph(&graph);
+        match gen.try_gen_graph_changes_near(&updates, &[]) {
+            Err(GenError::NoHistory) => (),
+            other => panic!("expected a missing history error, got {:?}", other),
+        }
+        let mut gen = GraphDataGenerator::new_from_seed(7).with_history(true);
+        gen.gen_initial_graph(&graph);
+        let changes = gen.try_gen_graph_changes_near(&updates, &[]).unwrap();
+        assert_eq!(changes.iter().filter(|(_, diff)| *diff < 0).count(), 2);
     }
 
-    pub fn elapsed(&self) -> std::time::Duration {
-        self.total_timer.elapsed()
+    #[test]
+    fn missing_saved_graph_is_an_error() {
+        let weight_par = WeightParameters { weight_range: (1, 10), rng_seed: 10, distribution: WeightDist::Uniform };
+        let path = std::env::temp_dir().join(format!("timely-path-test-{}-missing.bin", std::process::id()));
+        let graph = GraphBenchmarkData::SavedGraph { path_to_binary: path.to_str().unwrap().to_string(), weight_par: weight_par };
+        match GraphDataGenerator::new_from_seed(7).try_gen_initial_graph(&graph) {
+            Err(GenError::Load(GraphLoadError::Io(_))) => (),
+            other => panic!("expected a load error, got {:?}", other),
+        }
+        match GraphDataGenerator::new_from_seed(7).gen_initial_graph_partitioned(&graph, 1, 2) {
+            Err(GenError::Load(GraphLoadError::Io(_))) => (),
+            other => panic!("expected a load error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn benchmark_options_are_parsed_and_stripped() {
+        let arguments = "sssp random 10 20 1 5 3 2 0 1 inspect --seed 7 -w 2 --symmetric --max-distance 4"
+            .split(' ')
+            .map(|argument| argument.to_string())
+            .collect::<Vec<_>>();
+        // Parsing looks up every option, which must all be in the table.
+        let benchmark = try_parse_graph_benchmark_arguments(arguments.clone().into_iter()).unwrap();
+        assert_eq!(benchmark.seed, 7);
+        assert!(benchmark.symmetric);
+        assert_eq!(benchmark.max_distance, Some(4));
+        assert_eq!(without_benchmark_options(arguments.into_iter()).join(" "), "sssp random 10 20 1 5 3 2 0 1 inspect -w 2");
     }
 }
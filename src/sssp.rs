@@ -0,0 +1,459 @@
+/// Single source shortest path dataflows in differential dataflow, shared by the
+/// benchmarks and usable in other dataflows.
+
+use timely::communication::Allocate;
+use timely::dataflow::operators::probe::Handle as ProbeHandle;
+use timely::dataflow::operators::{Exchange, Filter, Inspect, Probe};
+use timely::dataflow::Scope;
+use timely::progress::Timestamp;
+use timely::worker::Worker;
+
+use differential_dataflow::difference::Semigroup;
+use differential_dataflow::input::{Input, InputSession};
+use differential_dataflow::lattice::Lattice;
+use differential_dataflow::operators::*;
+use differential_dataflow::AsCollection;
+use differential_dataflow::Collection;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::{AddAssign, Mul};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::workers::{accumulate_on_worker_zero, gen_initial_graph_parallel};
+use crate::{BenchmarkDescription, GraphBenchmarkUpdates, GraphDataGenerator};
+use crate::{Node, Weight, WeightedEdge, UnweightedEdge};
+
+/// A change of a distance inside the iterative scope: (node, distance, round, iteration, diff).
+pub type IterationRecord<T> = (Node, Weight, T, u64, isize);
+
+/// Returns pairs (n, s) indicating node n can be reached from a root at distance s.
+pub fn sssp<G: Scope>(
+    edges: &Collection<G, WeightedEdge>,
+    roots: &Collection<G, Node>,
+) -> Collection<G, (Node, Weight)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    sssp_recording(edges, roots, None)
+}
+
+/// `sssp` that also records every change of the distances inside the iterative scope
+/// in `snapshots` on worker 0, which slows down the computation.
+pub fn sssp_with_snapshots<G: Scope>(
+    edges: &Collection<G, WeightedEdge>,
+    roots: &Collection<G, Node>,
+    snapshots: Rc<RefCell<Vec<IterationRecord<G::Timestamp>>>>,
+) -> Collection<G, (Node, Weight)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    sssp_recording(edges, roots, Some(snapshots))
+}
+
+fn sssp_recording<G: Scope>(
+    edges: &Collection<G, WeightedEdge>,
+    roots: &Collection<G, Node>,
+    snapshots: Option<Rc<RefCell<Vec<IterationRecord<G::Timestamp>>>>>,
+) -> Collection<G, (Node, Weight)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // initialize roots as reaching themselves at distance 0
+    let nodes = roots.map(|x| (x, 0));
+    // Repeatedly update minimal distances each node can be reached from each root
+    nodes.iterate(|inner| {
+        if let Some(snapshots) = snapshots {
+            inner
+                .inner
+                .exchange(|_| 0)
+                .inspect(move |((node, distance), time, diff)| {
+                    snapshots.borrow_mut().push((*node, *distance, time.outer.clone(), time.inner, *diff));
+                });
+        }
+        let edges = edges
+            .enter(&inner.scope())
+            .map(|(from, to, w)| (from, (to, w)));
+        let nodes = nodes.enter(&inner.scope());
+        inner
+            .join_map(&edges, |_from, &cost, &(to, w)| (to, cost + w))
+            .concat(&nodes)
+            // Note: reduce receives its input as an ordered collection.
+            .reduce(|_, input, output| output.push((*input[0].0, 1)))
+    })
+}
+
+/// Returns pairs (n, (s, p)) indicating node n can be reached from a root at distance s,
+/// with p the node before it on a shortest path. Roots are their own predecessors, and
+/// ties are broken towards the smallest predecessor.
+pub fn sssp_with_predecessors<G: Scope>(
+    edges: &Collection<G, WeightedEdge>,
+    roots: &Collection<G, Node>,
+) -> Collection<G, (Node, (Weight, Node))>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    // initialize roots as reaching themselves at distance 0
+    let nodes = roots.map(|x| (x, (0, x)));
+    // Repeatedly update minimal distances each node can be reached from each root
+    nodes.iterate(|inner| {
+        let edges = edges
+            .enter(&inner.scope())
+            .map(|(from, to, w)| (from, (to, w)));
+        let nodes = nodes.enter(&inner.scope());
+        inner
+            .join_map(&edges, |&from, &(cost, _), &(to, w)| (to, (cost + w, from)))
+            .concat(&nodes)
+            // Note: reduce receives its input as an ordered collection, sorted by distance and
+            // then predecessor whatever order the updates arrived in, so the first is the same
+            // (distance, predecessor) pair in every run and with any number of workers.
+            .reduce(|_, input, output| output.push((*input[0].0, 1)))
+    })
+}
+
+/// Walk the predecessors back from `target` to the nearest of the `sources` and return the
+/// nodes of the path in order from that source, or `None` if the target is not reached. Zero
+/// weight cycles can make the predecessors circular, which also gives `None`.
+pub fn reconstruct_path(predecessors: &HashMap<Node, (Weight, Node)>, sources: &[Node], target: Node) -> Option<Vec<Node>> {
+    let mut path = vec![target];
+    let mut node = target;
+    while !sources.contains(&node) {
+        node = predecessors.get(&node)?.1;
+        if path.len() > predecessors.len() {
+            return None;
+        }
+        path.push(node);
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// The number of nodes with a distance and the largest distance, as a single record
+/// once any node is reached.
+pub fn reached_summary<G: Scope>(distances: &Collection<G, (Node, Weight)>) -> Collection<G, (usize, Weight)>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    distances
+        .map(|(_, distance)| ((), distance))
+        // The input is ordered, so the last distance is the largest.
+        .reduce(|_, input, output| {
+            let count: isize = input.iter().map(|(_, count)| *count).sum();
+            output.push(((count as usize, *input[input.len() - 1].0), 1));
+        })
+        .map(|((), summary)| summary)
+}
+
+/// The edges with their source and target swapped and the same weights, for searching
+/// backwards from a target.
+pub fn reverse<G: Scope>(edges: &Collection<G, WeightedEdge>) -> Collection<G, WeightedEdge> {
+    edges.map(|(from, to, weight)| (to, from, weight))
+}
+
+/// The edges together with their reverse, for searching a graph as undirected. Self loops
+/// and edges already listed in both directions then occur twice, which does not change
+/// the distances computed by `sssp`.
+pub fn undirected<G: Scope>(edges: &Collection<G, WeightedEdge>) -> Collection<G, WeightedEdge> {
+    edges.concat(&reverse(edges))
+}
+
+/// Distance carried as the difference of a collection. Adding two keeps the minimum,
+/// and multiplying them adds the distances. The weights are unsigned, as the minimum
+/// only settles without negative weights; files with negative weights fail to load.
+#[derive(
+    Abomonation, Copy, Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash,
+)]
+pub struct MinSum {
+    pub value: Weight,
+}
+
+impl<'a> AddAssign<&'a Self> for MinSum {
+    fn add_assign(&mut self, rhs: &'a Self) {
+        self.value = std::cmp::min(self.value, rhs.value);
+    }
+}
+
+impl Mul<Self> for MinSum {
+    type Output = Self;
+    // Saturates rather than overflowing on very long paths.
+    fn mul(self, rhs: Self) -> Self {
+        MinSum {
+            value: self.value.saturating_add(rhs.value),
+        }
+    }
+}
+
+impl Semigroup for MinSum {
+    fn is_zero(&self) -> bool {
+        false
+    }
+}
+
+/// Returns the nodes reached from a root, with their distance as the `MinSum` difference.
+/// Iterations are counted in a `u32`, which no path in a graph of `Node` indices outgrows.
+pub fn sssp_monoid<G: Scope>(
+    edges: &Collection<G, UnweightedEdge, MinSum>,
+    roots: &Collection<G, Node, MinSum>,
+) -> Collection<G, Node, MinSum>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    sssp_monoid_width::<G, u32>(edges, roots, None)
+}
+
+/// `sssp_monoid` that drops paths longer than `max_distance` in the relaxation, so the
+/// nodes only reachable through them are left out of the result.
+pub fn sssp_monoid_bounded<G: Scope>(
+    edges: &Collection<G, UnweightedEdge, MinSum>,
+    roots: &Collection<G, Node, MinSum>,
+    max_distance: Weight,
+) -> Collection<G, Node, MinSum>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    sssp_monoid_width::<G, u32>(edges, roots, Some(max_distance))
+}
+
+/// `sssp_monoid` with iterations counted in a `u16`, for smaller timestamps. Only safe when
+/// every node is reached by a shortest path of fewer than 65535 edges, see `sssp_monoid_width`.
+pub fn sssp_monoid_u16<G: Scope>(
+    edges: &Collection<G, UnweightedEdge, MinSum>,
+    roots: &Collection<G, Node, MinSum>,
+    max_distance: Option<Weight>,
+) -> Collection<G, Node, MinSum>
+where
+    G::Timestamp: Lattice + Ord,
+{
+    sssp_monoid_width::<G, u16>(edges, roots, max_distance)
+}
+
+/// `sssp_monoid` with iterations counted in `T`. Iteration `i` improves the distances
+/// reached over paths of `i` edges, so the computation of each round needs as many
+/// iterations as the shortest path with the most edges, counted in its fewest edges,
+/// plus one. This bound on the hop diameter from the roots must stay below the largest
+/// value of `T`: timely drops messages whose timestamp would overflow, which leaves
+/// the distances of the farther nodes silently wrong rather than failing.
+pub fn sssp_monoid_width<G: Scope, T>(
+    edges: &Collection<G, UnweightedEdge, MinSum>,
+    roots: &Collection<G, Node, MinSum>,
+    max_distance: Option<Weight>,
+) -> Collection<G, Node, MinSum>
+where
+    G::Timestamp: Lattice + Ord,
+    T: Timestamp<Summary = T> + Lattice + Ord + From<u8>,
+{
+    // repeatedly update minimal distances each node can be reached from each root
+    roots.scope().iterative::<T, _, _>(|scope| {
+        use differential_dataflow::operators::iterate::SemigroupVariable;
+        use differential_dataflow::operators::reduce::ReduceCore;
+        use differential_dataflow::trace::implementations::ord::OrdKeySpine as DefaultKeyTrace;
+
+        use timely::order::Product;
+        let variable = SemigroupVariable::new(scope, Product::new(Default::default(), T::from(1)));
+
+        let edges = edges.enter(scope);
+        let roots = roots.enter(scope);
+
+        let mut relaxed = variable
+            .map(|n| (n, ()))
+            .join_map(&edges, |_k, &(), d| *d);
+        if let Some(max_distance) = max_distance {
+            relaxed = relaxed
+                .inner
+                .filter(move |(_, _, distance)| distance.value <= max_distance)
+                .as_collection();
+        }
+
+        let result = relaxed
+            .concat(&roots)
+            .map(|x| (x, ()))
+            .reduce_core::<_, DefaultKeyTrace<_, _, _>>("Reduce", |_key, input, output, updates| {
+                if output.is_empty() || input[0].1 < output[0].1 {
+                    updates.push(((), input[0].1));
+                }
+            })
+            .as_collection(|k, ()| *k);
+
+        variable.set(&result);
+        result.leave()
+    })
+}
+
+/// The parts of the update rounds of an SSSP benchmark that differ between `run_sssp` and
+/// the `sssp_differential` binary, called by `run_update_rounds`.
+pub trait UpdateRounds<A: Allocate> {
+    /// The changes of the graph in `round`, counting the warmup rounds. Only called on worker 0.
+    fn changes(&mut self, round: u32) -> Vec<(WeightedEdge, isize)>;
+    /// Wait for measured batch `batch` to be computed by calling `wait`, for example to time it.
+    fn measured_batch(&mut self, worker: &mut Worker<A>, _batch: u32, wait: &mut dyn FnMut(&mut Worker<A>)) {
+        wait(worker);
+    }
+    /// Called once a warmup batch has been computed.
+    fn warmup_batch(&mut self, _worker: &mut Worker<A>) {}
+}
+
+/// Feed the warmup and measured update rounds of `desc` into `graph_in`, batched by
+/// `--rounds-per-batch`, and wait on `probe` for each batch to be computed. Round `r`
+/// is inserted at time `1 + r`, after the initial graph at time 0.
+pub fn run_update_rounds<A: Allocate, H: UpdateRounds<A>>(
+    worker: &mut Worker<A>,
+    desc: &BenchmarkDescription,
+    graph_in: &mut InputSession<u32, WeightedEdge, isize>,
+    probe: &ProbeHandle<u32>,
+    hooks: &mut H,
+) {
+    let warmup_rounds = desc.warmup_rounds;
+    for round in 0..warmup_rounds + desc.num_rounds {
+        if worker.index() == 0 {
+            for (edge, diff) in hooks.changes(round).into_iter() {
+                graph_in.update_at(edge, 1 + round, diff);
+            }
+        }
+        if !desc.ends_batch(round) {
+            // The changes stay at their round's time until the batch is complete.
+            continue;
+        }
+        graph_in.advance_to(2 + round);
+        graph_in.flush();
+        let time = *graph_in.time();
+        let mut wait = |worker: &mut Worker<A>| worker.step_while(|| probe.less_than(&time));
+        if round < warmup_rounds {
+            wait(worker);
+            hooks.warmup_batch(worker);
+        } else {
+            hooks.measured_batch(worker, (round - warmup_rounds) / desc.rounds_per_batch, &mut wait);
+        }
+    }
+}
+
+/// The `count` nodes closest to the sources among the accumulated (node, distance)
+/// records, ties broken by node, from which adversarial updates start.
+pub fn closest_nodes(distances: &HashMap<(Node, Weight), isize>, count: usize) -> Vec<Node> {
+    let mut reached = distances.keys().cloned().collect::<Vec<(Node, Weight)>>();
+    reached.sort_by_key(|&(node, distance)| (distance, node));
+    reached.into_iter().map(|(node, _)| node).take(count).collect()
+}
+
+/// The update rounds of `run_sssp`, which times each measured batch.
+struct ReportedRounds<'a> {
+    desc: &'a BenchmarkDescription,
+    gen: &'a mut GraphDataGenerator,
+    distances: Rc<RefCell<HashMap<(Node, Weight), isize>>>,
+    num_near_nodes: usize,
+    rounds: Vec<Duration>,
+}
+
+impl<'a, A: Allocate> UpdateRounds<A> for ReportedRounds<'a> {
+    fn changes(&mut self, _round: u32) -> Vec<(WeightedEdge, isize)> {
+        let near_nodes = closest_nodes(&self.distances.borrow(), self.num_near_nodes);
+        self.gen.gen_graph_changes_near(&self.desc.graph_updates, &near_nodes)
+    }
+
+    fn measured_batch(&mut self, worker: &mut Worker<A>, _batch: u32, wait: &mut dyn FnMut(&mut Worker<A>)) {
+        let round_timer = Instant::now();
+        wait(worker);
+        self.rounds.push(round_timer.elapsed());
+    }
+}
+
+/// Durations and result of a run of `run_sssp`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// Generating or loading the initial graph.
+    pub loading: Duration,
+    /// Computing the distances on the initial graph.
+    pub initial: Duration,
+    /// Catching up with each batch of measured update rounds, without the warmup rounds.
+    pub rounds: Vec<Duration>,
+    pub total: Duration,
+    /// Distance of the target after the last round, `None` if it is not reachable.
+    pub target_distance: Option<Weight>,
+}
+
+/// Run the differential SSSP benchmark of `desc` on `workers` worker threads of this process,
+/// as the `sssp_differential` binary does without its optional outputs and analyses. The graph,
+/// updates, query, seed, warmup and batching of the description are used, as well as
+/// `--parallel-loading` and `--symmetric`. The durations are measured on worker 0.
+pub fn run_sssp(desc: &BenchmarkDescription, workers: usize) -> BenchmarkReport {
+    let desc = desc.clone();
+    let guards = timely::execute(timely::Configuration::Process(workers), move |worker| {
+        let total_timer = Instant::now();
+        let worker_index = worker.index();
+        // Adversarial updates start from as many of the nodes closest to the source as there are new edges.
+        let (num_near_nodes, deletions) = match desc.graph_updates {
+            GraphBenchmarkUpdates::AdversarialUpdates { edges_per_update, .. } => (std::cmp::max(1, edges_per_update as usize), false),
+            GraphBenchmarkUpdates::RandomUpdates { deletion_ratio, .. } => (0, deletion_ratio > 0.0),
+        };
+        let target = desc.search_query.target;
+        // Accumulated (node, distance) updates gathered on worker 0, of every node for
+        // adversarial updates and of the target otherwise.
+        let distances = Rc::new(RefCell::new(HashMap::new()));
+        let mut probe = ProbeHandle::new();
+        let (mut roots, mut graph_in) = worker.dataflow(|scope| {
+            let (root_input, roots) = scope.new_collection();
+            let (edge_input, graph) = scope.new_collection();
+            let mut result = sssp(&graph, &roots);
+            if num_near_nodes == 0 {
+                result = result.filter(move |(node, _)| *node == target);
+            }
+            accumulate_on_worker_zero(&result, distances.clone()).probe_with(&mut probe);
+            (root_input, edge_input)
+        });
+        for source in desc.search_query.sources.iter() {
+            roots.insert(*source);
+        }
+        roots.close();
+
+        let loading_timer = Instant::now();
+        let mut gen = GraphDataGenerator::new_from_seed(desc.seed).with_symmetric(desc.symmetric).with_history(deletions);
+        if desc.parallel_loading {
+            let edges = gen_initial_graph_parallel(worker, &mut gen, &desc.graph_data).unwrap_or_else(|error| panic!("{}", error));
+            for edge in edges.into_iter() {
+                graph_in.update_at(edge, Default::default(), 1);
+            }
+        } else if worker_index == 0 {
+            for edge in gen.gen_initial_graph(&desc.graph_data).into_iter() {
+                graph_in.update_at(edge, Default::default(), 1);
+            }
+        }
+        let loading = loading_timer.elapsed();
+        let initial_timer = Instant::now();
+        graph_in.advance_to(1);
+        graph_in.flush();
+        worker.step_while(|| probe.less_than(graph_in.time()));
+        let initial = initial_timer.elapsed();
+
+        let mut rounds = ReportedRounds {
+            desc: &desc,
+            gen: &mut gen,
+            distances: distances.clone(),
+            num_near_nodes: num_near_nodes,
+            rounds: Vec::new(),
+        };
+        run_update_rounds(worker, &desc, &mut graph_in, &probe, &mut rounds);
+        let rounds = rounds.rounds;
+
+        let target_distance = distances
+            .borrow()
+            .keys()
+            .filter(|(node, _)| *node == target)
+            .map(|(_, distance)| *distance)
+            .min();
+        BenchmarkReport {
+            loading: loading,
+            initial: initial,
+            rounds: rounds,
+            total: total_timer.elapsed(),
+            target_distance: target_distance,
+        }
+    })
+    .expect("Could not start the timely workers");
+    // Only worker 0 gathers the distances and generates the updates.
+    guards
+        .join()
+        .into_iter()
+        .next()
+        .expect("There must be at least one worker")
+        .expect("Timely worker failed")
+}
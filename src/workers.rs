@@ -0,0 +1,150 @@
+/// Coordination between the timely workers outside of the benchmark dataflows.
+
+use timely::communication::{Allocate, Allocator};
+use timely::dataflow::operators::probe::Handle as ProbeHandle;
+use timely::dataflow::operators::{Broadcast, Exchange, Input, Inspect, Probe};
+use timely::dataflow::scopes::Child;
+use timely::dataflow::{Scope, Stream};
+use timely::worker::Worker;
+
+use differential_dataflow::difference::Semigroup;
+use differential_dataflow::input::Input as CollectionInput;
+use differential_dataflow::Collection;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use crate::{GenError, GraphBenchmarkData, GraphDataGenerator, WeightedEdge};
+
+/// The values passed by all workers, in no particular order. Every worker must call this
+/// at the same point, as it builds a small dataflow of its own.
+pub fn gather_from_workers<A: Allocate>(worker: &mut Worker<A>, value: u64) -> Vec<u64> {
+    let gathered = Rc::new(RefCell::new(Vec::new()));
+    let (mut input, probe) = {
+        let gathered = gathered.clone();
+        worker.dataflow::<u64, _, _>(|scope| {
+            let (input, stream) = scope.new_input::<u64>();
+            let probe = stream
+                .broadcast()
+                .inspect(move |value| gathered.borrow_mut().push(*value))
+                .probe();
+            (input, probe)
+        })
+    };
+    input.send(value);
+    input.close();
+    worker.step_while(|| !probe.done());
+    let values = gathered.borrow().clone();
+    values
+}
+
+/// Generate or load the share of this worker of the initial graph, with
+/// `GraphDataGenerator::gen_initial_graph_partitioned`, and set the number of nodes and
+/// edges of the generator to the totals over all workers. If any worker fails, all of them
+/// return an error, the others `GenError::OtherWorkerFailed`, so none waits for another.
+pub fn gen_initial_graph_parallel<A: Allocate>(
+    worker: &mut Worker<A>,
+    gen: &mut GraphDataGenerator,
+    desc: &GraphBenchmarkData,
+) -> Result<Vec<WeightedEdge>, GenError> {
+    let edges = gen.gen_initial_graph_partitioned(desc, worker.index(), worker.peers());
+    // Every worker takes part in all the gathers, also after an error.
+    let failures: u64 = gather_from_workers(worker, edges.is_err() as u64).into_iter().sum();
+    let num_nodes = gather_from_workers(worker, gen.max_num_nodes() as u64).into_iter().max().unwrap_or(0);
+    let num_edges: u64 = gather_from_workers(worker, edges.as_ref().map_or(0, |edges| edges.len()) as u64).into_iter().sum();
+    let edges = edges?;
+    if failures > 0 {
+        return Err(GenError::OtherWorkerFailed);
+    }
+    gen.set_max_num_nodes(num_nodes as u32);
+    gen.set_total_edges(num_edges as usize);
+    Ok(edges)
+}
+
+/// Accumulate the updates of `collection` on worker 0 into `accumulated`, which holds every
+/// record with its total difference, without the records whose differences cancel out.
+/// Returns the updates, for attaching a probe.
+pub fn accumulate_on_worker_zero<G, D, R>(
+    collection: &Collection<G, D, R>,
+    accumulated: Rc<RefCell<HashMap<D, R>>>,
+) -> Stream<G, (D, G::Timestamp, R)>
+where
+    G: Scope,
+    G::Timestamp: timely::ExchangeData,
+    D: timely::ExchangeData + Hash + Eq,
+    R: Semigroup + timely::ExchangeData,
+{
+    collection
+        .inner
+        .exchange(|_| 0)
+        .inspect(move |(record, _time, diff)| {
+            let mut accumulated = accumulated.borrow_mut();
+            let retracted = match accumulated.get_mut(record) {
+                Some(total) => {
+                    *total += diff;
+                    total.is_zero()
+                }
+                None => {
+                    accumulated.insert(record.clone(), diff.clone());
+                    diff.is_zero()
+                }
+            };
+            if retracted {
+                accumulated.remove(record);
+            }
+        })
+}
+
+/// A scope of `run_query`, at the top level of a worker.
+pub type QueryScope<'a> = Child<'a, Worker<Allocator>, usize>;
+
+/// Run the dataflow built by `query` from the `edges` and `roots` on `workers` worker
+/// threads of this process, and return its result accumulated on worker 0 as by
+/// `accumulate_on_worker_zero`. Worker 0 inserts the updates of the inputs at time 0, for
+/// one-off queries and tests on a fixed graph.
+pub fn run_query<E, RE, N, RN, D, R, F>(workers: usize, edges: Vec<(E, RE)>, roots: Vec<(N, RN)>, query: F) -> HashMap<D, R>
+where
+    E: differential_dataflow::Data + Send + Sync,
+    RE: Semigroup + Send + Sync,
+    N: differential_dataflow::Data + Send + Sync,
+    RN: Semigroup + Send + Sync,
+    D: differential_dataflow::Data + timely::ExchangeData + Hash + Eq + Send,
+    R: Semigroup + timely::ExchangeData + Send,
+    F: for<'a> Fn(&Collection<QueryScope<'a>, E, RE>, &Collection<QueryScope<'a>, N, RN>) -> Collection<QueryScope<'a>, D, R>
+        + Send
+        + Sync
+        + 'static,
+{
+    let guards = timely::execute(timely::Configuration::Process(workers), move |worker| {
+        let accumulated = Rc::new(RefCell::new(HashMap::new()));
+        let mut probe = ProbeHandle::new();
+        let (mut edge_input, mut root_input) = worker.dataflow::<usize, _, _>(|scope| {
+            let (edge_input, edges) = scope.new_collection();
+            let (root_input, roots) = scope.new_collection();
+            accumulate_on_worker_zero(&query(&edges, &roots), accumulated.clone()).probe_with(&mut probe);
+            (edge_input, root_input)
+        });
+        if worker.index() == 0 {
+            for (edge, diff) in edges.iter() {
+                edge_input.update(edge.clone(), diff.clone());
+            }
+            for (root, diff) in roots.iter() {
+                root_input.update(root.clone(), diff.clone());
+            }
+        }
+        edge_input.close();
+        root_input.close();
+        worker.step_while(|| !probe.done());
+        let accumulated = accumulated.borrow().clone();
+        accumulated
+    })
+    .expect("Could not start the timely workers");
+    guards
+        .join()
+        .into_iter()
+        .next()
+        .expect("There must be at least one worker")
+        .expect("Timely worker failed")
+}
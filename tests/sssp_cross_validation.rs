@@ -0,0 +1,77 @@
+// Cross-validation of the differential SSSP dataflows, plain and with the distance as
+// the difference, against Dijkstra's algorithm.
+
+extern crate graph_utility;
+
+use graph_utility::sssp::{sssp, sssp_monoid, MinSum};
+use graph_utility::workers::run_query;
+use graph_utility::{adjacency_list, default_rng, dijkstra, generate_weighted_graph};
+use graph_utility::{Node, WeightDist, WeightParameters, WeightedEdge};
+
+use std::collections::HashMap;
+
+/// Distances computed by `sssp` on `workers` worker threads, gathered on worker 0.
+fn differential_distances(edges: &[WeightedEdge], sources: &[Node], workers: usize) -> HashMap<Node, u64> {
+    let edges = edges.iter().map(|edge| (*edge, 1)).collect();
+    let roots = sources.iter().map(|source| (*source, 1)).collect();
+    run_query(workers, edges, roots, |graph, roots| sssp(graph, roots))
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|((node, distance), _)| (node, distance as u64))
+        .collect()
+}
+
+/// Distances computed by `sssp_monoid` on `workers` worker threads, gathered on worker 0.
+fn monoid_distances(edges: &[WeightedEdge], sources: &[Node], workers: usize) -> HashMap<Node, u64> {
+    let edges = edges.iter().map(|&(from, to, weight)| ((from, to), MinSum { value: weight })).collect();
+    let roots = sources.iter().map(|source| (*source, MinSum { value: 0 })).collect();
+    run_query(workers, edges, roots, |graph, roots| sssp_monoid(graph, roots))
+        .into_iter()
+        .map(|(node, distance)| (node, distance.value as u64))
+        .collect()
+}
+
+/// Distances of the reached nodes computed by `dijkstra`.
+fn dijkstra_distances(edges: &[WeightedEdge], sources: &[Node]) -> HashMap<Node, u64> {
+    let num_nodes = sources.iter().map(|source| source + 1).max().unwrap_or(0);
+    dijkstra(&adjacency_list(edges, num_nodes), sources)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(node, distance)| distance.map(|distance| (node as Node, distance)))
+        .collect()
+}
+
+fn assert_matches_dijkstra(edges: &[WeightedEdge], sources: &[Node]) {
+    let expected = dijkstra_distances(edges, sources);
+    for workers in [1, 3].iter() {
+        assert_eq!(differential_distances(edges, sources, *workers), expected, "{} workers on {:?}", workers, edges);
+        assert_eq!(monoid_distances(edges, sources, *workers), expected, "monoid, {} workers on {:?}", workers, edges);
+    }
+}
+
+#[test]
+fn shortcut_is_not_taken_when_longer() {
+    assert_matches_dijkstra(&[(0, 1, 4), (1, 2, 1), (0, 2, 7), (2, 3, 2), (3, 1, 1)], &[0]);
+}
+
+#[test]
+fn zero_weight_cycle_and_unreachable_nodes() {
+    assert_matches_dijkstra(&[(0, 1, 0), (1, 0, 0), (1, 2, 3), (4, 5, 1)], &[0]);
+}
+
+#[test]
+fn self_loops_and_parallel_edges() {
+    assert_matches_dijkstra(&[(0, 0, 1), (0, 1, 3), (0, 1, 2), (1, 2, 2), (2, 2, 0)], &[0]);
+}
+
+#[test]
+fn several_sources() {
+    assert_matches_dijkstra(&[(0, 2, 5), (1, 2, 1), (2, 3, 1), (3, 0, 2)], &[0, 1]);
+}
+
+#[test]
+fn random_graph() {
+    let weight_par = WeightParameters { weight_range: (1, 10), rng_seed: 10, distribution: WeightDist::Uniform };
+    let edges = generate_weighted_graph(&mut default_rng(7), 50, 200, &weight_par);
+    assert_matches_dijkstra(&edges, &[0]);
+}
@@ -0,0 +1,45 @@
+// Determinism of the predecessors of `sssp_with_predecessors` on equally short paths.
+
+extern crate graph_utility;
+
+use graph_utility::sssp::{reconstruct_path, sssp_with_predecessors};
+use graph_utility::workers::run_query;
+use graph_utility::{Node, Weight, WeightedEdge};
+
+use std::collections::HashMap;
+
+/// Two paths of weight 2 from 0 to 3, through 2 and through 1, listed with the larger
+/// predecessor first. Node 5 is reached at distance 2 both directly from 0 and through 1,
+/// and node 4 lies behind 3.
+const EQUAL_PATHS: &[WeightedEdge] = &[(0, 2, 1), (2, 3, 1), (0, 1, 1), (1, 3, 1), (3, 4, 2), (1, 5, 1), (0, 5, 2)];
+
+/// Predecessors computed by `sssp_with_predecessors` on `workers` worker threads, gathered on worker 0.
+fn predecessors(edges: &[WeightedEdge], sources: &[Node], workers: usize) -> HashMap<Node, (Weight, Node)> {
+    let edges = edges.iter().map(|edge| (*edge, 1)).collect();
+    let roots = sources.iter().map(|source| (*source, 1)).collect();
+    run_query(workers, edges, roots, |graph, roots| sssp_with_predecessors(graph, roots))
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|((node, predecessor), _)| (node, predecessor))
+        .collect()
+}
+
+#[test]
+fn ties_go_to_the_smallest_predecessor() {
+    let predecessors = predecessors(EQUAL_PATHS, &[0], 1);
+    assert_eq!(predecessors[&0], (0, 0));
+    assert_eq!(predecessors[&3], (2, 1));
+    assert_eq!(predecessors[&5], (2, 0));
+    assert_eq!(reconstruct_path(&predecessors, &[0], 4), Some(vec![0, 1, 3, 4]));
+    assert_eq!(reconstruct_path(&predecessors, &[0], 6), None);
+}
+
+#[test]
+fn predecessors_do_not_depend_on_the_run() {
+    let first = predecessors(EQUAL_PATHS, &[0], 1);
+    for workers in [1, 3].iter() {
+        let again = predecessors(EQUAL_PATHS, &[0], *workers);
+        assert_eq!(again, first, "{} workers", workers);
+        assert_eq!(reconstruct_path(&again, &[0], 4), reconstruct_path(&first, &[0], 4), "{} workers", workers);
+    }
+}